@@ -0,0 +1,14 @@
+#![no_main]
+
+use contract1::Faucet;
+use libfuzzer_sys::fuzz_target;
+use sdk::StateCommitment;
+
+// Decoding a corrupt state commitment must fail gracefully.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(faucet) = Faucet::try_from(StateCommitment(data.to_vec())) {
+        // Anything that decodes must re-encode to a decodable state.
+        let commitment = faucet.try_commit().expect("re-encoding decoded state");
+        Faucet::try_from(commitment).expect("decoding re-encoded state");
+    }
+});
@@ -0,0 +1,30 @@
+#![no_main]
+
+use contract1::{Faucet, FaucetAction, Nonced};
+use libfuzzer_sys::fuzz_target;
+use sdk::{Blob, BlobData, BlobIndex, BlobTransaction, Calldata, Hashed, Identity, ZkContract};
+
+// Arbitrary blob bytes must never make the contract panic, whether or not they parse.
+fuzz_target!(|data: &[u8]| {
+    let identity = Identity("fuzz@faucet".to_string());
+    let blobs = vec![Blob {
+        contract_name: "faucet".into(),
+        data: BlobData(data.to_vec()),
+    }];
+    let tx = BlobTransaction::new(identity.clone(), blobs.clone());
+    let calldata = Calldata {
+        identity,
+        index: BlobIndex(0),
+        tx_blob_count: blobs.len(),
+        blobs: blobs.into(),
+        tx_hash: tx.hashed(),
+        tx_ctx: None,
+        private_input: vec![],
+    };
+
+    let _ = sdk::utils::parse_calldata::<Nonced<FaucetAction>>(&calldata);
+
+    let mut faucet = Faucet::new();
+    let _ = faucet.execute(&calldata);
+    let _ = faucet.commit();
+});
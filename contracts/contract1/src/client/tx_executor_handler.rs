@@ -10,9 +10,9 @@ impl TxExecutorHandler for Faucet {
     }
 
     fn handle(&mut self, calldata: &Calldata) -> Result<sdk::HyleOutput> {
-        let initial_state_commitment = <Self as ZkContract>::commit(self);
+        let initial_state_commitment = self.try_commit()?;
         let mut res = <Self as ZkContract>::execute(self, calldata);
-        let next_state_commitment = <Self as ZkContract>::commit(self);
+        let next_state_commitment = self.try_commit()?;
         Ok(as_hyle_output(
             initial_state_commitment,
             next_state_commitment,
@@ -26,9 +26,10 @@ impl TxExecutorHandler for Faucet {
     }
 
     fn construct_state(
-        _register_blob: &sdk::RegisterContractEffect,
+        register_blob: &sdk::RegisterContractEffect,
         _metadata: &Option<Vec<u8>>,
     ) -> anyhow::Result<Self> {
-        Ok(Self::default())
+        Self::try_from(register_blob.state_commitment.clone())
+            .context("Failed to construct faucet state from registration")
     }
 }
@@ -1,5 +1,3 @@
-use std::str;
-
 use anyhow::{anyhow, Context, Result};
 use client_sdk::{
     contract_indexer::{
@@ -48,9 +46,10 @@ impl ContractHandler for Faucet {
         };
 
         let hyle_output = self.handle(&calldata).map_err(|e| anyhow::anyhow!(e))?;
-        let program_outputs = str::from_utf8(&hyle_output.program_outputs).unwrap_or("no output");
-
-        sdk::info!("🚀 Executed {contract_name}: {}", program_outputs);
+        match FaucetEvent::from_program_outputs(&hyle_output.program_outputs) {
+            Some(event) => sdk::info!("🚀 Executed {contract_name}: {:?}", event),
+            None => sdk::info!("🚀 Executed {contract_name}: no output"),
+        }
         sdk::tracing::debug!(
             handler = %contract_name,
             "hyle_output: {:?}", hyle_output
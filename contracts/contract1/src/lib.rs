@@ -1,5 +1,6 @@
 use borsh::{io::Error, BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use sdk::{ContractName, Identity, RunResult};
 
@@ -29,58 +30,193 @@ pub enum SmtTokenAction {
     },
 }
 
+impl SmtTokenAction {
+    pub fn as_blob(
+        &self,
+        contract_name: sdk::ContractName,
+        caller: Option<sdk::BlobIndex>,
+    ) -> sdk::Blob {
+        sdk::Blob {
+            contract_name,
+            data: sdk::BlobData::from(sdk::StructuredBlobData {
+                caller,
+                callees: None,
+                parameters: self.clone(),
+            }),
+        }
+    }
+}
+
 impl sdk::FullStateRevert for Faucet {}
 
 impl sdk::ZkContract for Faucet {
     /// Entry point of the contract's logic
     fn execute(&mut self, calldata: &sdk::Calldata) -> RunResult {
         // Parse contract inputs
-        let (action, mut ctx) = sdk::utils::parse_calldata::<Nonced<FaucetAction>>(calldata)?;
+        let (action, ctx) = sdk::utils::parse_calldata::<Nonced<FaucetAction>>(calldata)
+            .map_err(FaucetError::InvalidCalldata)?;
         let identity = calldata.identity.clone();
+        let block_height = calldata.tx_ctx.as_ref().map(|tx_ctx| tx_ctx.block_height.0);
+        let day = calldata
+            .tx_ctx
+            .as_ref()
+            .map(|tx_ctx| (tx_ctx.timestamp.0 / DAY_MS) as u64);
+
+        if self.is_banned(&identity) {
+            return Err(FaucetError::Banned { identity }.into());
+        }
+        self.check_nonce(&identity, action.nonce)?;
 
         // Execute the given action
-        let res = match action.action {
+        let event = match action.action {
             FaucetAction::Click => {
-                // ctx.is_in_callee_blobs(
-                //     &ContractName("oranj".to_string()),
-                //     SmtTokenAction::Transfer {
-                //         amount: 1,
-                //         sender: ctx.contract_name.0.clone().into(),
-                //         recipient: identity.0.rsplit_once('@').unwrap().0.into(),
-                //     },
-                // )?;
-
-                "Clicked".to_string()
-                // self.click(identity)?
-            } // FaucetAction::BuyPowerup { name } => self.buy_powerup(identity, &name)?,
-              // FaucetAction::Cashout => {
-              //     let transfer = sdk::utils::parse_structured_blob::<HyllarAction>(
-              //         &calldata.blobs,
-              //         &sdk::BlobIndex(calldata.index.0 + 1),
-              //     )
-              //     .expect("Failed to parse transfer blob");
-              //
-              //     let HyllarAction::Transfer { recipient, amount } = transfer.data.parameters else {
-              //         return Err("Hyllar blob is not a transfer".to_string());
-              //     };
-              //
-              //     if format!("{}@{}", recipient, ctx.contract_name) != identity.to_string() {
-              //         return Err("Recipient does not match the tx identity".to_string());
-              //     }
-              //
-              //     self.cashout(identity, amount)?
-              // }
+                self.check_work(&identity, action.nonce, action.work)?;
+                self.click(identity.clone(), block_height, day)?
+            }
+            FaucetAction::BuyPowerup { name } => self.buy_powerup(identity.clone(), &name)?,
+            FaucetAction::Cashout => {
+                let amount =
+                    cashout_transfer_amount(calldata, &ctx.contract_name, &self.token_contract)?;
+                self.cashout(identity.clone(), amount)?
+            }
+            FaucetAction::RegisterReferrer { referrer } => {
+                self.register_referrer(identity.clone(), referrer)?
+            }
+            FaucetAction::SetClickDifficulty { difficulty } => {
+                self.set_click_difficulty(&identity, difficulty)?
+            }
+            FaucetAction::Ban {
+                identity: banned,
+                reason,
+            } => self.ban(&identity, banned, reason)?,
+            FaucetAction::Unban { identity: unbanned } => self.unban(&identity, unbanned)?,
+            FaucetAction::EndSeason { carry_over_percent } => {
+                self.end_season(&identity, carry_over_percent)?
+            }
+            FaucetAction::Prestige => self.prestige(identity.clone())?,
+            FaucetAction::JoinTeam { team } => {
+                // The team cooldown is counted in blocks.
+                let block_height = block_height.ok_or_else(|| {
+                    FaucetError::InvalidCalldata(
+                        "Joining a team needs the transaction context".to_string(),
+                    )
+                })?;
+                self.join_team(identity.clone(), team, block_height)?
+            }
+            FaucetAction::SetStreakTiers { tiers } => self.set_streak_tiers(&identity, tiers)?,
+            FaucetAction::SetReferralPercent { percent } => {
+                self.set_referral_percent(&identity, percent)?
+            }
+            FaucetAction::SetPrestige { thresholds, factor } => {
+                self.set_prestige(&identity, thresholds, factor)?
+            }
         };
 
-        Ok((res.into_bytes(), ctx, vec![]))
+        self.get_or_create_player(identity).last_nonce = action.nonce;
+
+        let program_outputs = borsh::to_vec(&event).map_err(|e| e.to_string())?;
+
+        Ok((program_outputs, ctx, vec![]))
     }
 
     /// In this example, we serialize the full state on-chain.
+    ///
+    /// Empty if the state can't be encoded, see [`Faucet::try_commit`]. The guest program
+    /// calls `try_commit` and fails the transaction instead.
     fn commit(&self) -> sdk::StateCommitment {
-        sdk::StateCommitment(self.as_bytes().expect("Failed to encsode Balances"))
+        self.try_commit().unwrap_or_default()
     }
 }
 
+/// Length of a streak day, in milliseconds of block time (UTC days).
+pub const DAY_MS: u128 = 24 * 60 * 60 * 1000;
+
+/// Highest proof of work difficulty an admin can set, in leading zero bits.
+pub const MAX_CLICK_DIFFICULTY: u8 = 32;
+
+/// Leading zero bits of `sha256(identity || nonce || work)`, integers little endian.
+///
+/// Binding the work to the identity and the nonce, which can't be reused, makes it
+/// valid for a single click.
+pub fn work_bits(identity: &Identity, nonce: u64, work: u64) -> u32 {
+    let hash = Sha256::new()
+        .chain_update(identity.0.as_bytes())
+        .chain_update(nonce.to_le_bytes())
+        .chain_update(work.to_le_bytes())
+        .finalize();
+    let mut bits = 0;
+    for byte in hash {
+        bits += byte.leading_zeros();
+        if byte != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+/// Finds the smallest `work` meeting `difficulty` for a click of `identity` with `nonce`.
+pub fn solve_work(identity: &Identity, nonce: u64, difficulty: u8) -> u64 {
+    (0..)
+        .find(|work| work_bits(identity, nonce, *work) >= u32::from(difficulty))
+        .unwrap_or_default()
+}
+
+/// Most prestige levels an admin can set.
+pub const MAX_PRESTIGE_LEVEL: u32 = 16;
+
+/// Longest team name, in bytes.
+pub const MAX_TEAM_NAME_LEN: usize = 32;
+
+/// Sha256 of the borsh-encoded standings of a season.
+pub fn standings_hash(standings: &[(Identity, u128)]) -> [u8; 32] {
+    Sha256::digest(borsh::to_vec(standings).unwrap_or_default()).into()
+}
+
+/// Returns the amount of the token transfer that must follow a cashout blob,
+/// checking it is paid in `token_contract` by the faucet to the player of the transaction.
+fn cashout_transfer_amount(
+    calldata: &sdk::Calldata,
+    faucet: &ContractName,
+    token_contract: &str,
+) -> Result<u128, FaucetError> {
+    let transfer = sdk::utils::parse_structured_blob::<SmtTokenAction>(
+        &calldata.blobs,
+        &sdk::BlobIndex(calldata.index.0 + 1),
+    )
+    .ok_or_else(|| FaucetError::InvalidTransfer("Missing token transfer blob".to_string()))?;
+
+    if transfer.contract_name.0 != token_contract {
+        return Err(FaucetError::InvalidTransfer(format!(
+            "Expected a {} blob, got {}",
+            token_contract, transfer.contract_name
+        )));
+    }
+
+    let SmtTokenAction::Transfer {
+        sender,
+        recipient,
+        amount,
+    } = transfer.data.parameters
+    else {
+        return Err(FaucetError::InvalidTransfer(
+            "Token blob is not a transfer".to_string(),
+        ));
+    };
+
+    if sender.0 != faucet.0 {
+        return Err(FaucetError::InvalidTransfer(
+            "Transfer is not sent by the faucet".to_string(),
+        ));
+    }
+    if format!("{}@{}", recipient, faucet) != calldata.identity.0 {
+        return Err(FaucetError::InvalidTransfer(
+            "Recipient does not match the tx identity".to_string(),
+        ));
+    }
+
+    Ok(amount)
+}
+
 impl Faucet {
     pub fn new() -> Self {
         let powerups = vec![Powerup::Multiplier {
@@ -92,40 +228,492 @@ impl Faucet {
         Self {
             players: HashMap::new(),
             available_powerups: powerups,
+            max_clicks_per_block: 0,
+            token_contract: String::new(),
+            referral_percent: 10,
+            admins: vec![],
+            click_difficulty: 0,
+            banned: BTreeSet::new(),
+            season: 0,
+            season_standings: vec![],
+            prestige_thresholds: vec![10_000, 100_000, 1_000_000],
+            prestige_factor: 2,
+            teams: BTreeMap::new(),
+            team_switch_cooldown_blocks: 1_000,
+            streak_tiers: vec![
+                StreakTier::new(3, 10),
+                StreakTier::new(7, 25),
+                StreakTier::new(30, 50),
+            ],
+        }
+    }
+
+    /// Bonus tiers of the daily streaks, by increasing number of days.
+    pub fn streak_tiers(&self) -> &[StreakTier] {
+        &self.streak_tiers
+    }
+
+    /// Bonus on the click points of a player active `streak` days in a row.
+    pub fn streak_bonus_percent(&self, streak: u32) -> u32 {
+        StreakTier::bonus_percent(&self.streak_tiers, streak)
+    }
+
+    pub fn set_streak_tiers(
+        &mut self,
+        identity: &Identity,
+        tiers: Vec<StreakTier>,
+    ) -> Result<FaucetEvent, FaucetError> {
+        self.check_admin(identity)?;
+        if tiers.iter().any(|tier| tier.days == 0) {
+            return Err(FaucetError::InvalidStreakTiers {
+                reason: "tiers start from 1 day".to_string(),
+            });
         }
+        if tiers.windows(2).any(|w| w[0].days >= w[1].days) {
+            return Err(FaucetError::InvalidStreakTiers {
+                reason: "days must increase from one tier to the next".to_string(),
+            });
+        }
+        self.streak_tiers = tiers.clone();
+        Ok(FaucetEvent::StreakTiersSet { tiers })
+    }
+
+    pub fn teams(&self) -> &BTreeMap<String, Team> {
+        &self.teams
+    }
+
+    pub fn team_switch_cooldown_blocks(&self) -> u64 {
+        self.team_switch_cooldown_blocks
+    }
+
+    /// Players of `team`.
+    pub fn team_members<'a>(
+        &'a self,
+        team: &'a str,
+    ) -> impl Iterator<Item = (&'a Identity, &'a PlayerState)> {
+        self.players
+            .iter()
+            .filter(move |(_, p)| p.team.as_deref() == Some(team))
+    }
+
+    /// Moves `identity` to `team`, creating it if needed.
+    ///
+    /// Points earned before joining stay with the previous team. Switching again is only
+    /// possible `team_switch_cooldown_blocks` after joining.
+    pub fn join_team(
+        &mut self,
+        identity: Identity,
+        team: String,
+        block_height: u64,
+    ) -> Result<FaucetEvent, FaucetError> {
+        if team.is_empty() || team.len() > MAX_TEAM_NAME_LEN {
+            return Err(FaucetError::InvalidTeam {
+                team,
+                reason: format!("names are 1 to {MAX_TEAM_NAME_LEN} bytes long"),
+            });
+        }
+        let (previous, joined_at) = self
+            .player(&identity)
+            .map(|p| (p.team.clone(), p.team_joined_at))
+            .unwrap_or_default();
+        if previous.as_ref() == Some(&team) {
+            return Err(FaucetError::InvalidTeam {
+                team,
+                reason: "already a member".to_string(),
+            });
+        }
+        if previous.is_some() {
+            let until_block = joined_at.saturating_add(self.team_switch_cooldown_blocks);
+            if block_height < until_block {
+                return Err(FaucetError::TeamSwitchCooldown { until_block });
+            }
+        }
+
+        if let Some(previous) = &previous {
+            if let Some(previous) = self.teams.get_mut(previous) {
+                previous.members = previous.members.saturating_sub(1);
+            }
+        }
+        let members = &mut self.teams.entry(team.clone()).or_default().members;
+        *members = members.saturating_add(1);
+        let player = self.get_or_create_player(identity);
+        player.team = Some(team.clone());
+        player.team_joined_at = block_height;
+
+        Ok(FaucetEvent::TeamJoined { team, previous })
+    }
+
+    /// Points needed to leave prestige `level`, `None` past the last level.
+    pub fn prestige_threshold(&self, level: u32) -> Option<u128> {
+        self.prestige_thresholds.get(level as usize).copied()
+    }
+
+    pub fn prestige_thresholds(&self) -> &[u128] {
+        &self.prestige_thresholds
+    }
+
+    pub fn prestige_factor(&self) -> u32 {
+        self.prestige_factor
+    }
+
+    /// Applies to the next prestiges; players keep the level they reached.
+    pub fn set_prestige(
+        &mut self,
+        identity: &Identity,
+        thresholds: Vec<u128>,
+        factor: u32,
+    ) -> Result<FaucetEvent, FaucetError> {
+        self.check_admin(identity)?;
+        if factor == 0 {
+            return Err(FaucetError::InvalidPrestige {
+                reason: "the factor must be at least 1".to_string(),
+            });
+        }
+        if thresholds.len() > MAX_PRESTIGE_LEVEL as usize {
+            return Err(FaucetError::InvalidPrestige {
+                reason: format!("at most {MAX_PRESTIGE_LEVEL} levels"),
+            });
+        }
+        self.prestige_thresholds = thresholds.clone();
+        self.prestige_factor = factor;
+        Ok(FaucetEvent::PrestigeSet { thresholds, factor })
+    }
+
+    /// Factor applied to the click points of a player at prestige `level`.
+    pub fn prestige_multiplier(&self, level: u32) -> u128 {
+        (self.prestige_factor.max(1) as u128).saturating_pow(level)
+    }
+
+    /// Trades the points and powerups of a player for the next prestige level.
+    pub fn prestige(&mut self, identity: Identity) -> Result<FaucetEvent, FaucetError> {
+        let (current, level) = self
+            .player(&identity)
+            .map(|p| (p.points, p.prestige))
+            .unwrap_or((0, 0));
+        let required = self
+            .prestige_threshold(level)
+            .ok_or(FaucetError::PrestigeUnavailable)?;
+        if current < required {
+            return Err(FaucetError::InsufficientPoints { required, current });
+        }
+
+        let player = self.get_or_create_player(identity);
+        player.points = 0;
+        player.multiplier = 1;
+        player.owned_powerups.clear();
+        player.prestige += 1;
+        let level = player.prestige;
+
+        Ok(FaucetEvent::Prestiged {
+            level,
+            multiplier: self.prestige_multiplier(level),
+        })
+    }
+
+    /// Number of seasons that ended.
+    pub fn season(&self) -> u32 {
+        self.season
+    }
+
+    /// Hash of the final standings of each ended season, season 1 first.
+    pub fn season_standings(&self) -> &[[u8; 32]] {
+        &self.season_standings
+    }
+
+    /// Points of every player, ordered by identity, as hashed by [`standings_hash`].
+    pub fn standings(&self) -> Vec<(Identity, u128)> {
+        let mut standings: Vec<_> = self
+            .players
+            .iter()
+            .map(|(identity, player)| (identity.clone(), player.points))
+            .collect();
+        standings.sort();
+        standings
+    }
+
+    /// Records the hash of the standings, then keeps `carry_over_percent` of every player's
+    /// points into the next season. Multipliers and powerups are kept.
+    pub fn end_season(
+        &mut self,
+        admin: &Identity,
+        carry_over_percent: u8,
+    ) -> Result<FaucetEvent, FaucetError> {
+        self.check_admin(admin)?;
+        if carry_over_percent > 100 {
+            return Err(FaucetError::InvalidCarryOver { carry_over_percent });
+        }
+
+        let standings_hash = standings_hash(&self.standings());
+        for player in self.players.values_mut() {
+            player.points = player.points.saturating_mul(carry_over_percent as u128) / 100;
+        }
+        self.season_standings.push(standings_hash);
+        self.season += 1;
+
+        Ok(FaucetEvent::SeasonEnded {
+            season: self.season,
+            standings_hash,
+            carry_over_percent,
+        })
+    }
+
+    pub fn is_banned(&self, identity: &Identity) -> bool {
+        self.banned.contains(identity)
+    }
+
+    /// Rejects every further transaction of `banned`. Its points are kept.
+    pub fn ban(
+        &mut self,
+        admin: &Identity,
+        banned: Identity,
+        reason: String,
+    ) -> Result<FaucetEvent, FaucetError> {
+        self.check_admin(admin)?;
+        if self.is_admin(&banned) {
+            return Err(FaucetError::CannotBanAdmin { identity: banned });
+        }
+        self.banned.insert(banned.clone());
+        Ok(FaucetEvent::Banned {
+            identity: banned,
+            reason,
+        })
+    }
+
+    pub fn unban(
+        &mut self,
+        admin: &Identity,
+        unbanned: Identity,
+    ) -> Result<FaucetEvent, FaucetError> {
+        self.check_admin(admin)?;
+        self.banned.remove(&unbanned);
+        Ok(FaucetEvent::Unbanned { identity: unbanned })
+    }
+
+    /// Identities allowed to run the admin actions.
+    pub fn with_admins(mut self, admins: Vec<Identity>) -> Self {
+        self.admins = admins;
+        self
+    }
+
+    /// Caps the clicks of a player in a single block, 0 disables the limit.
+    pub fn with_max_clicks_per_block(mut self, max_clicks_per_block: u32) -> Self {
+        self.max_clicks_per_block = max_clicks_per_block;
+        self
+    }
+
+    /// Token contract paying out cashouts.
+    pub fn with_token_contract(mut self, token_contract: String) -> Self {
+        self.token_contract = token_contract;
+        self
+    }
+
+    pub fn max_clicks_per_block(&self) -> u32 {
+        self.max_clicks_per_block
+    }
+
+    pub fn token_contract(&self) -> &str {
+        &self.token_contract
+    }
+
+    pub fn is_admin(&self, identity: &Identity) -> bool {
+        self.admins.contains(identity)
+    }
+
+    fn check_admin(&self, identity: &Identity) -> Result<(), FaucetError> {
+        if self.is_admin(identity) {
+            Ok(())
+        } else {
+            Err(FaucetError::Unauthorized {
+                identity: identity.clone(),
+            })
+        }
+    }
+
+    /// Leading zero bits required of the clicks' proof of work, 0 when disabled.
+    pub fn click_difficulty(&self) -> u8 {
+        self.click_difficulty
+    }
+
+    fn check_work(&self, identity: &Identity, nonce: u64, work: u64) -> Result<(), FaucetError> {
+        if work_bits(identity, nonce, work) < u32::from(self.click_difficulty) {
+            return Err(FaucetError::InsufficientWork {
+                difficulty: self.click_difficulty,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn set_click_difficulty(
+        &mut self,
+        identity: &Identity,
+        difficulty: u8,
+    ) -> Result<FaucetEvent, FaucetError> {
+        self.check_admin(identity)?;
+        if difficulty > MAX_CLICK_DIFFICULTY {
+            return Err(FaucetError::InvalidDifficulty {
+                difficulty,
+                max: MAX_CLICK_DIFFICULTY,
+            });
+        }
+        self.click_difficulty = difficulty;
+        Ok(FaucetEvent::ClickDifficultySet { difficulty })
+    }
+
+    pub fn player(&self, identity: &Identity) -> Option<&PlayerState> {
+        self.players.get(identity)
+    }
+
+    /// Powerups players can buy.
+    pub fn powerups(&self) -> &[Powerup] {
+        &self.available_powerups
     }
 
     fn get_or_create_player(&mut self, identity: Identity) -> &mut PlayerState {
         self.players.entry(identity).or_insert_with(|| PlayerState {
-            points: 0,
             multiplier: 1,
-            owned_powerups: Vec::new(),
+            ..Default::default()
         })
     }
 
-    pub fn click(&mut self, identity: Identity) -> Result<String, String> {
+    /// Nonces must strictly increase for each player, so a blob can't be replayed.
+    fn check_nonce(&self, identity: &Identity, nonce: u64) -> Result<(), FaucetError> {
+        match self.players.get(identity) {
+            Some(player) if nonce <= player.last_nonce => Err(FaucetError::NonceReused {
+                nonce,
+                last_nonce: player.last_nonce,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn click(
+        &mut self,
+        identity: Identity,
+        block_height: Option<u64>,
+        day: Option<u64>,
+    ) -> Result<FaucetEvent, FaucetError> {
+        let max_clicks_per_block = self.max_clicks_per_block;
+        let referral_percent = self.referral_percent as u128;
+        let prestige_factor = self.prestige_factor.max(1) as u128;
+        let streak_tiers = self.streak_tiers.clone();
+        let player = self.get_or_create_player(identity);
+
+        if let Some(block_height) = block_height {
+            if player.last_click_block != block_height {
+                player.last_click_block = block_height;
+                player.clicks_in_block = 0;
+            }
+            if max_clicks_per_block != 0 && player.clicks_in_block >= max_clicks_per_block {
+                return Err(FaucetError::RateLimited {
+                    max_clicks_per_block,
+                });
+            }
+            player.clicks_in_block += 1;
+        }
+
+        if let Some(day) = day {
+            player.record_active_day(day);
+        }
+        let bonus_percent = StreakTier::bonus_percent(&streak_tiers, player.streak) as u128;
+        let gain = player
+            .multiplier
+            .saturating_mul(prestige_factor.saturating_pow(player.prestige));
+        let gain = gain.saturating_add(gain.saturating_mul(bonus_percent) / 100);
+        player.points = player.points.saturating_add(gain);
+        let event = FaucetEvent::Clicked {
+            points: player.points,
+            multiplier: player.multiplier,
+        };
+        let team = player.team.clone();
+
+        if let Some(referrer) = player.referrer.clone() {
+            // Credited on the running total so that small clicks still add up for the referrer.
+            let previous = player.referred_points.saturating_mul(referral_percent) / 100;
+            player.referred_points = player.referred_points.saturating_add(gain);
+            let credit = (player.referred_points.saturating_mul(referral_percent) / 100)
+                .saturating_sub(previous);
+            if credit > 0 {
+                let referrer = self.get_or_create_player(referrer);
+                referrer.points = referrer.points.saturating_add(credit);
+                referrer.referral_points = referrer.referral_points.saturating_add(credit);
+            }
+        }
+        if let Some(team) = team.and_then(|t| self.teams.get_mut(&t)) {
+            team.points = team.points.saturating_add(gain);
+        }
+
+        Ok(event)
+    }
+
+    /// Sets who referred `identity` to the faucet. It can only be set once.
+    pub fn register_referrer(
+        &mut self,
+        identity: Identity,
+        referrer: Identity,
+    ) -> Result<FaucetEvent, FaucetError> {
+        if referrer == identity {
+            return Err(FaucetError::InvalidReferrer {
+                referrer,
+                reason: "a player can't refer itself".to_string(),
+            });
+        }
+        if self.player(&referrer).is_none() {
+            return Err(FaucetError::InvalidReferrer {
+                referrer,
+                reason: "unknown player".to_string(),
+            });
+        }
+        if let Some(existing) = self.player(&identity).and_then(|p| p.referrer.clone()) {
+            return Err(FaucetError::ReferrerAlreadySet { referrer: existing });
+        }
+
         let player = self.get_or_create_player(identity);
-        player.points += player.multiplier;
-        Ok(format!(
-            "Clicked! Points: {}, Multiplier: {}",
-            player.points, player.multiplier
-        ))
+        player.referrer = Some(referrer.clone());
+        Ok(FaucetEvent::ReferrerRegistered { referrer })
+    }
+
+    /// Players referred by `referrer`.
+    pub fn referees<'a>(
+        &'a self,
+        referrer: &'a Identity,
+    ) -> impl Iterator<Item = (&'a Identity, &'a PlayerState)> {
+        self.players
+            .iter()
+            .filter(move |(_, p)| p.referrer.as_ref() == Some(referrer))
+    }
+
+    /// Percentage of the referees' click points credited to their referrer.
+    pub fn referral_percent(&self) -> u8 {
+        self.referral_percent
+    }
+
+    /// Applies to the referees' points from the next click on; credits already paid stay.
+    pub fn set_referral_percent(
+        &mut self,
+        identity: &Identity,
+        percent: u8,
+    ) -> Result<FaucetEvent, FaucetError> {
+        self.check_admin(identity)?;
+        if percent > 100 {
+            return Err(FaucetError::InvalidReferralPercent { percent });
+        }
+        self.referral_percent = percent;
+        Ok(FaucetEvent::ReferralPercentSet { percent })
     }
 
     pub fn buy_powerup(
         &mut self,
         identity: Identity,
         powerup_name: &str,
-    ) -> Result<String, String> {
+    ) -> Result<FaucetEvent, FaucetError> {
         let powerup = self
             .available_powerups
             .iter()
-            .find(|p| match p {
-                Powerup::Multiplier { name, .. } => name == powerup_name,
-            })
+            .find(|p| p.name() == powerup_name)
             .cloned()
-            .ok_or("Powerup not found")?;
-        let player = self.get_or_create_player(identity);
+            .ok_or_else(|| FaucetError::UnknownPowerup {
+                name: powerup_name.to_string(),
+            })?;
 
         match powerup {
             Powerup::Multiplier {
@@ -133,39 +721,50 @@ impl Faucet {
                 price,
                 multiplier_bonus,
             } => {
-                if player.points >= price {
-                    player.points -= price;
-                    player.multiplier += multiplier_bonus;
-                    player.owned_powerups.push(name.clone());
-                    Ok(format!(
-                        "Powerup bought! Name: {}, New Multiplier: {}",
-                        name, player.multiplier
-                    ))
-                } else {
-                    Err(format!(
-                        "Not enough points to buy powerup. Required: {}, Current: {}",
-                        price, player.points
-                    ))
+                let current = self.player(&identity).map(|p| p.points).unwrap_or(0);
+                if current < price {
+                    return Err(FaucetError::InsufficientPoints {
+                        required: price,
+                        current,
+                    });
                 }
+
+                let player = self.get_or_create_player(identity);
+                player.points -= price;
+                player.multiplier = player.multiplier.saturating_add(multiplier_bonus);
+                player.owned_powerups.push(name.clone());
+                Ok(FaucetEvent::PowerupBought {
+                    name,
+                    price,
+                    multiplier: player.multiplier,
+                })
             }
         }
     }
 
-    pub fn cashout(&mut self, identity: Identity, amount: u128) -> Result<String, String> {
-        let player = self.get_or_create_player(identity);
-        if player.points >= amount {
-            player.points -= amount;
-            Ok(format!("Cashout successful! Amount: {}", amount))
-        } else {
-            Err(format!(
-                "Not enough points to cash out. Required: {}, Current: {}",
-                amount, player.points
-            ))
+    pub fn cashout(
+        &mut self,
+        identity: Identity,
+        amount: u128,
+    ) -> Result<FaucetEvent, FaucetError> {
+        let current = self.player(&identity).map(|p| p.points).unwrap_or(0);
+        if current < amount {
+            return Err(FaucetError::InsufficientPoints {
+                required: amount,
+                current,
+            });
         }
+
+        let player = self.get_or_create_player(identity);
+        player.points -= amount;
+        Ok(FaucetEvent::CashedOut {
+            amount,
+            points: player.points,
+        })
     }
 }
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Default)]
 pub struct PlayerState {
@@ -173,10 +772,143 @@ pub struct PlayerState {
     multiplier: u128,
 
     owned_powerups: Vec<String>,
+
+    #[serde(default)]
+    last_nonce: u64,
+    #[serde(default)]
+    last_click_block: u64,
+    #[serde(default)]
+    clicks_in_block: u32,
+
+    /// Player who referred this one, set once.
+    #[serde(default)]
+    referrer: Option<Identity>,
+    /// Points this player earned by clicking since it registered its referrer.
+    #[serde(default)]
+    referred_points: u128,
+    /// Points this player was credited for its referees' clicks.
+    #[serde(default)]
+    referral_points: u128,
+    /// Permanent level multiplying the click points, see [`Faucet::prestige_multiplier`].
+    #[serde(default)]
+    prestige: u32,
+    #[serde(default)]
+    team: Option<String>,
+    /// Block height at which the player joined its team.
+    #[serde(default)]
+    team_joined_at: u64,
+    /// Day, counted from the unix epoch, of the player's last click.
+    #[serde(default)]
+    last_active_day: u64,
+    /// Consecutive days the player clicked, up to `last_active_day`.
+    #[serde(default)]
+    streak: u32,
+}
+
+impl PlayerState {
+    pub fn points(&self) -> u128 {
+        self.points
+    }
+
+    pub fn multiplier(&self) -> u128 {
+        self.multiplier
+    }
+
+    pub fn owned_powerups(&self) -> &[String] {
+        &self.owned_powerups
+    }
+
+    pub fn referrer(&self) -> Option<&Identity> {
+        self.referrer.as_ref()
+    }
+
+    pub fn referred_points(&self) -> u128 {
+        self.referred_points
+    }
+
+    pub fn referral_points(&self) -> u128 {
+        self.referral_points
+    }
+
+    pub fn prestige(&self) -> u32 {
+        self.prestige
+    }
+
+    pub fn team(&self) -> Option<&str> {
+        self.team.as_deref()
+    }
+
+    pub fn streak(&self) -> u32 {
+        self.streak
+    }
+
+    pub fn last_active_day(&self) -> u64 {
+        self.last_active_day
+    }
+
+    /// Streak reached by clicking on `day`: extended when it follows the last active day,
+    /// restarted after a gap.
+    pub fn streak_on(&self, day: u64) -> u32 {
+        if self.streak > 0 && day == self.last_active_day {
+            self.streak
+        } else if self.streak > 0 && day == self.last_active_day + 1 {
+            self.streak.saturating_add(1)
+        } else {
+            1
+        }
+    }
+
+    fn record_active_day(&mut self, day: u64) {
+        self.streak = self.streak_on(day);
+        self.last_active_day = day;
+    }
+}
+
+/// Click bonus of the players active at least `days` days in a row.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StreakTier {
+    pub days: u32,
+    pub bonus_percent: u32,
+}
+
+impl StreakTier {
+    pub fn new(days: u32, bonus_percent: u32) -> Self {
+        StreakTier {
+            days,
+            bonus_percent,
+        }
+    }
+
+    /// Bonus of the highest of `tiers` reached by `streak`.
+    fn bonus_percent(tiers: &[StreakTier], streak: u32) -> u32 {
+        tiers
+            .iter()
+            .rev()
+            .find(|tier| tier.days <= streak)
+            .map(|tier| tier.bonus_percent)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Team {
+    members: u32,
+    /// Click points earned by the players while they were members.
+    points: u128,
+}
+
+impl Team {
+    pub fn members(&self) -> u32 {
+        self.members
+    }
+
+    pub fn points(&self) -> u128 {
+        self.points
+    }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone)]
-enum Powerup {
+pub enum Powerup {
     Multiplier {
         name: String,
         price: u128,
@@ -184,45 +916,343 @@ enum Powerup {
     },
 }
 
+impl Powerup {
+    pub fn name(&self) -> &str {
+        match self {
+            Powerup::Multiplier { name, .. } => name,
+        }
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Faucet {
     players: HashMap<Identity, PlayerState>,
     available_powerups: Vec<Powerup>,
+    /// Maximum clicks accepted per player in a single block, 0 disables the limit.
+    #[serde(default)]
+    max_clicks_per_block: u32,
+    /// Percentage of a referee's click points credited to its referrer.
+    #[serde(default)]
+    referral_percent: u8,
+    #[serde(default)]
+    admins: Vec<Identity>,
+    /// Leading zero bits required of the clicks' proof of work, 0 disables it.
+    #[serde(default)]
+    click_difficulty: u8,
+    /// Identities whose transactions are rejected.
+    #[serde(default)]
+    banned: BTreeSet<Identity>,
+    /// Number of seasons that ended.
+    #[serde(default)]
+    season: u32,
+    /// Hash of the final standings of each ended season.
+    #[serde(default)]
+    season_standings: Vec<[u8; 32]>,
+    /// Points needed to leave each prestige level.
+    #[serde(default)]
+    prestige_thresholds: Vec<u128>,
+    /// Click points are multiplied by `prestige_factor ^ prestige`.
+    #[serde(default)]
+    prestige_factor: u32,
+    #[serde(default)]
+    teams: BTreeMap<String, Team>,
+    /// Blocks a player stays in a team before it can switch.
+    #[serde(default)]
+    team_switch_cooldown_blocks: u64,
+    #[serde(default)]
+    streak_tiers: Vec<StreakTier>,
+    /// Token contract paying out cashouts, which are rejected while it is empty.
+    #[serde(default)]
+    token_contract: String,
 }
 
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub struct Nonced<T> {
     pub action: T,
     pub nonce: u64,
+    /// Proof of work of clicks, see [`work_bits`].
+    pub work: u64,
+}
+
+impl Nonced<FaucetAction> {
+    /// Builds the faucet blob for this action, as `Faucet::execute` parses it.
+    pub fn as_blob(&self, contract_name: sdk::ContractName) -> sdk::Blob {
+        sdk::Blob {
+            contract_name,
+            data: sdk::BlobData::from(sdk::StructuredBlobData {
+                caller: None,
+                callees: None,
+                parameters: self.clone(),
+            }),
+        }
+    }
 }
 
 /// Enum representing possible calls to the contract functions.
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub enum FaucetAction {
     Click,
-    // BuyPowerup { name: String },
-    // Cashout,
+    BuyPowerup {
+        name: String,
+    },
+    Cashout,
+    RegisterReferrer {
+        referrer: Identity,
+    },
+    /// Admin only.
+    SetClickDifficulty {
+        difficulty: u8,
+    },
+    /// Admin only.
+    Ban {
+        identity: Identity,
+        reason: String,
+    },
+    /// Admin only.
+    Unban {
+        identity: Identity,
+    },
+    /// Admin only.
+    EndSeason {
+        carry_over_percent: u8,
+    },
+    Prestige,
+    JoinTeam {
+        team: String,
+    },
+    /// Admin only.
+    SetStreakTiers {
+        tiers: Vec<StreakTier>,
+    },
+    /// Admin only.
+    SetReferralPercent {
+        percent: u8,
+    },
+    /// Admin only.
+    SetPrestige {
+        thresholds: Vec<u128>,
+        factor: u32,
+    },
 }
 
 impl FaucetAction {
-    pub fn as_blob(&self, contract_name: sdk::ContractName) -> sdk::Blob {
-        sdk::Blob {
-            contract_name,
-            data: sdk::BlobData(borsh::to_vec(self).expect("Failed to encode FaucetAction")),
+    /// Builds the faucet blob for this action without proof of work.
+    pub fn as_blob(&self, contract_name: sdk::ContractName, nonce: u64) -> sdk::Blob {
+        Nonced {
+            action: self.clone(),
+            nonce,
+            work: 0,
         }
+        .as_blob(contract_name)
     }
 }
 
+/// Outcome of a successful faucet action, borsh-encoded as the program outputs.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum FaucetEvent {
+    Clicked {
+        points: u128,
+        multiplier: u128,
+    },
+    PowerupBought {
+        name: String,
+        price: u128,
+        multiplier: u128,
+    },
+    CashedOut {
+        amount: u128,
+        points: u128,
+    },
+    ReferrerRegistered {
+        referrer: Identity,
+    },
+    ClickDifficultySet {
+        difficulty: u8,
+    },
+    Banned {
+        identity: Identity,
+        reason: String,
+    },
+    Unbanned {
+        identity: Identity,
+    },
+    SeasonEnded {
+        /// Number of the season that ended, from 1.
+        season: u32,
+        standings_hash: [u8; 32],
+        carry_over_percent: u8,
+    },
+    Prestiged {
+        level: u32,
+        /// Factor now applied to the click points.
+        multiplier: u128,
+    },
+    TeamJoined {
+        team: String,
+        previous: Option<String>,
+    },
+    StreakTiersSet {
+        tiers: Vec<StreakTier>,
+    },
+    ReferralPercentSet {
+        percent: u8,
+    },
+    PrestigeSet {
+        thresholds: Vec<u128>,
+        factor: u32,
+    },
+}
+
+impl FaucetEvent {
+    pub fn from_program_outputs(outputs: &[u8]) -> Option<Self> {
+        borsh::from_slice(outputs).ok()
+    }
+}
+
+/// Reasons a faucet action can be rejected.
+///
+/// `RunResult` errors are strings, so the borsh encoding is carried hex-encoded in the
+/// program outputs of failed transactions; see [`FaucetError::from_program_outputs`].
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum FaucetError {
+    InvalidCalldata(String),
+    InsufficientPoints { required: u128, current: u128 },
+    UnknownPowerup { name: String },
+    NonceReused { nonce: u64, last_nonce: u64 },
+    RateLimited { max_clicks_per_block: u32 },
+    InvalidTransfer(String),
+    InvalidReferrer { referrer: Identity, reason: String },
+    ReferrerAlreadySet { referrer: Identity },
+    Unauthorized { identity: Identity },
+    InsufficientWork { difficulty: u8 },
+    InvalidDifficulty { difficulty: u8, max: u8 },
+    Banned { identity: Identity },
+    InvalidCarryOver { carry_over_percent: u8 },
+    PrestigeUnavailable,
+    InvalidTeam { team: String, reason: String },
+    TeamSwitchCooldown { until_block: u64 },
+    InvalidStreakTiers { reason: String },
+    InvalidReferralPercent { percent: u8 },
+    InvalidPrestige { reason: String },
+    CannotBanAdmin { identity: Identity },
+}
+
+impl FaucetError {
+    pub fn from_program_outputs(outputs: &[u8]) -> Option<Self> {
+        let bytes = hex::decode(outputs).ok()?;
+        borsh::from_slice(&bytes).ok()
+    }
+}
+
+impl From<FaucetError> for String {
+    fn from(error: FaucetError) -> Self {
+        hex::encode(borsh::to_vec(&error).unwrap_or_default())
+    }
+}
+
+impl core::fmt::Display for FaucetError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FaucetError::InvalidCalldata(e) => write!(f, "Invalid calldata: {e}"),
+            FaucetError::InsufficientPoints { required, current } => write!(
+                f,
+                "Not enough points. Required: {required}, Current: {current}"
+            ),
+            FaucetError::UnknownPowerup { name } => write!(f, "Powerup {name} not found"),
+            FaucetError::NonceReused { nonce, last_nonce } => {
+                write!(f, "Nonce {nonce} already used, last nonce is {last_nonce}")
+            }
+            FaucetError::RateLimited {
+                max_clicks_per_block,
+            } => write!(
+                f,
+                "Too many clicks, at most {max_clicks_per_block} per block"
+            ),
+            FaucetError::InvalidTransfer(e) => write!(f, "Invalid cashout transfer: {e}"),
+            FaucetError::InvalidReferrer { referrer, reason } => {
+                write!(f, "Invalid referrer {referrer}: {reason}")
+            }
+            FaucetError::ReferrerAlreadySet { referrer } => {
+                write!(f, "Referrer already set to {referrer}")
+            }
+            FaucetError::Unauthorized { identity } => {
+                write!(f, "{identity} is not a faucet admin")
+            }
+            FaucetError::InsufficientWork { difficulty } => {
+                write!(f, "Proof of work below difficulty {difficulty}")
+            }
+            FaucetError::InvalidDifficulty { difficulty, max } => {
+                write!(f, "Difficulty {difficulty} above the maximum of {max}")
+            }
+            FaucetError::Banned { identity } => write!(f, "{identity} is banned"),
+            FaucetError::InvalidCarryOver { carry_over_percent } => {
+                write!(f, "Carry-over of {carry_over_percent}% above 100%")
+            }
+            FaucetError::PrestigeUnavailable => write!(f, "No prestige level left"),
+            FaucetError::InvalidTeam { team, reason } => write!(f, "Invalid team {team}: {reason}"),
+            FaucetError::TeamSwitchCooldown { until_block } => {
+                write!(f, "Can't switch team before block {until_block}")
+            }
+            FaucetError::InvalidStreakTiers { reason } => {
+                write!(f, "Invalid streak tiers: {reason}")
+            }
+            FaucetError::InvalidReferralPercent { percent } => {
+                write!(f, "Referral percentage of {percent}% above 100%")
+            }
+            FaucetError::InvalidPrestige { reason } => {
+                write!(f, "Invalid prestige settings: {reason}")
+            }
+            FaucetError::CannotBanAdmin { identity } => {
+                write!(f, "{identity} is a faucet admin and can't be banned")
+            }
+        }
+    }
+}
+
+/// Errors raised while encoding or decoding the faucet state.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FaucetStateError {
+    Encode(String),
+    Decode(String),
+}
+
+impl core::fmt::Display for FaucetStateError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FaucetStateError::Encode(e) => write!(f, "Could not encode faucet state: {e}"),
+            FaucetStateError::Decode(e) => write!(f, "Could not decode faucet state: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for FaucetStateError {}
+
 impl Faucet {
     pub fn as_bytes(&self) -> Result<Vec<u8>, Error> {
         borsh::to_vec(self)
     }
+
+    /// Borsh only fails to encode into memory on collections longer than `u32::MAX`, which
+    /// the faucet never holds; the infallible encodings of this crate rely on it.
+    pub fn try_commit(&self) -> Result<sdk::StateCommitment, FaucetStateError> {
+        self.as_bytes()
+            .map(sdk::StateCommitment)
+            .map_err(|e| FaucetStateError::Encode(e.to_string()))
+    }
 }
 
-impl From<sdk::StateCommitment> for Faucet {
-    fn from(state: sdk::StateCommitment) -> Self {
-        borsh::from_slice(&state.0)
-            .map_err(|_| "Could not decode hyllar state".to_string())
-            .unwrap()
+impl TryFrom<sdk::StateCommitment> for Faucet {
+    type Error = FaucetStateError;
+
+    fn try_from(state: sdk::StateCommitment) -> Result<Self, Self::Error> {
+        Self::try_from(state.0.as_slice())
+    }
+}
+
+impl TryFrom<&[u8]> for Faucet {
+    type Error = FaucetStateError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        borsh::from_slice(bytes).map_err(|e| FaucetStateError::Decode(e.to_string()))
     }
 }
@@ -2,8 +2,9 @@
 
 use contract1::Faucet;
 use sdk::{
-    guest::{execute, GuestEnv, SP1Env},
-    Calldata,
+    guest::{GuestEnv, SP1Env},
+    utils::as_hyle_output,
+    Calldata, HyleOutput, StateCommitment, ZkContract,
 };
 
 sp1_zkvm::entrypoint!(main);
@@ -12,9 +13,68 @@ fn main() {
     let env = SP1Env {};
     let (commitment_metadata, calldata): (Vec<u8>, Vec<Calldata>) = env.read();
 
-    let outputs = execute::<Faucet>(&commitment_metadata, &calldata);
+    let outputs = match Faucet::try_from(commitment_metadata.as_slice()) {
+        Ok(contract) => execute(contract, &commitment_metadata, &calldata),
+        Err(e) => failed_outputs(&commitment_metadata, &calldata, e.to_string()),
+    };
 
     let vec = borsh::to_vec(&outputs).unwrap();
 
     sp1_zkvm::io::commit_slice(&vec);
 }
+
+/// Runs the batch on the decoded state, reverting the state of failed transactions.
+///
+/// A transaction whose resulting state can't be committed fails too, rather than proving
+/// a transition to a state the next batch couldn't decode.
+fn execute(
+    mut contract: Faucet,
+    commitment_metadata: &[u8],
+    calldata: &[Calldata],
+) -> Vec<HyleOutput> {
+    // The full state is committed on-chain, so the metadata is the current commitment.
+    let mut state = StateCommitment(commitment_metadata.to_vec());
+    calldata
+        .iter()
+        .map(|calldata| {
+            let checkpoint = contract.clone();
+            let mut res = contract.execute(calldata);
+            let next_state = match &res {
+                Ok(_) => contract.try_commit().map_err(|e| e.to_string()),
+                Err(e) => Err(e.clone()),
+            };
+            let next_state = match next_state {
+                Ok(next_state) => next_state,
+                Err(e) => {
+                    contract = checkpoint;
+                    res = Err(e);
+                    state.clone()
+                }
+            };
+            let output = as_hyle_output(state.clone(), next_state.clone(), calldata, &mut res);
+            state = next_state;
+            output
+        })
+        .collect()
+}
+
+/// Fails every transaction of the batch, leaving the state untouched, when it can't be decoded.
+fn failed_outputs(
+    commitment_metadata: &[u8],
+    calldata: &[Calldata],
+    error: String,
+) -> Vec<HyleOutput> {
+    // The full state is committed on-chain, so the metadata is the current commitment.
+    let state = StateCommitment(commitment_metadata.to_vec());
+    calldata
+        .iter()
+        .map(|calldata| {
+            as_hyle_output(
+                state.clone(),
+                state.clone(),
+                calldata,
+                &mut Err(error.clone()),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,381 @@
+use contract1::{Faucet, FaucetAction, FaucetError, Nonced, StreakTier};
+use proptest::prelude::*;
+use sdk::{BlobIndex, BlobTransaction, Calldata, Hashed, Identity, ZkContract};
+
+const PLAYERS: [&str; 3] = ["alice@faucet", "bob@faucet", "carol@faucet"];
+
+#[derive(Debug, Clone)]
+enum Op {
+    Execute {
+        player: usize,
+        action: Nonced<FaucetAction>,
+    },
+    Click {
+        player: usize,
+        block_height: Option<u64>,
+    },
+    BuyPowerup {
+        player: usize,
+        name: String,
+    },
+    Cashout {
+        player: usize,
+        amount: u128,
+    },
+}
+
+fn powerup_name() -> impl Strategy<Value = String> {
+    prop_oneof![Just("Wooden Click".to_string()), "[a-zA-Z ]{0,16}"]
+}
+
+fn action_strategy() -> impl Strategy<Value = FaucetAction> {
+    prop_oneof![
+        4 => Just(FaucetAction::Click),
+        1 => powerup_name().prop_map(|name| FaucetAction::BuyPowerup { name }),
+        1 => Just(FaucetAction::Cashout),
+        1 => (0..PLAYERS.len()).prop_map(|referrer| FaucetAction::RegisterReferrer {
+            referrer: PLAYERS[referrer].into(),
+        }),
+        1 => Just(FaucetAction::Prestige),
+    ]
+}
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    let player = 0..PLAYERS.len();
+    prop_oneof![
+        (player.clone(), action_strategy(), any::<u64>()).prop_map(|(player, action, nonce)| {
+            Op::Execute {
+                player,
+                action: Nonced {
+                    action,
+                    nonce,
+                    work: 0,
+                },
+            }
+        }),
+        (player.clone(), prop::option::of(0u64..4)).prop_map(|(player, block_height)| Op::Click {
+            player,
+            block_height
+        }),
+        (player.clone(), powerup_name()).prop_map(|(player, name)| Op::BuyPowerup { player, name }),
+        (player, 0u128..300).prop_map(|(player, amount)| Op::Cashout { player, amount }),
+    ]
+}
+
+fn calldata(identity: &Identity, action: &Nonced<FaucetAction>) -> Calldata {
+    let blobs = vec![action.as_blob("faucet".into())];
+    let tx = BlobTransaction::new(identity.clone(), blobs.clone());
+    Calldata {
+        identity: identity.clone(),
+        index: BlobIndex(0),
+        tx_blob_count: blobs.len(),
+        blobs: blobs.into(),
+        tx_hash: tx.hashed(),
+        tx_ctx: None,
+        private_input: vec![],
+    }
+}
+
+/// Points, multiplier and prestige level of a player, (0, 1, 0) if it never played.
+fn snapshot(faucet: &Faucet, identity: &Identity) -> (u128, u128, u32) {
+    faucet
+        .player(identity)
+        .map(|p| (p.points(), p.multiplier(), p.prestige()))
+        .unwrap_or((0, 1, 0))
+}
+
+proptest! {
+    #[test]
+    fn corrupt_state_is_an_error(bytes in prop::collection::vec(any::<u8>(), 0..64)) {
+        // Must never panic, whatever the bytes.
+        let _ = Faucet::try_from(sdk::StateCommitment(bytes));
+    }
+
+    #[test]
+    fn faucet_invariants_hold(ops in prop::collection::vec(op_strategy(), 0..64)) {
+        // Low thresholds, so that generated runs reach every prestige level.
+        let admin = Identity::from("admin@wallet");
+        let mut faucet = Faucet::new().with_admins(vec![admin.clone()]);
+        faucet.set_prestige(&admin, vec![5, 20], 2).unwrap();
+        let mut earned = [0u128; PLAYERS.len()];
+
+        for op in ops {
+            let player = match &op {
+                Op::Execute { player, .. }
+                | Op::Click { player, .. }
+                | Op::BuyPowerup { player, .. }
+                | Op::Cashout { player, .. } => *player,
+            };
+            let identity = Identity(PLAYERS[player].to_string());
+            let before = snapshot(&faucet, &identity);
+            // Clicks also credit the referrer, so every player's points are followed.
+            let points_before: Vec<_> = PLAYERS
+                .iter()
+                .map(|p| snapshot(&faucet, &Identity(p.to_string())).0)
+                .collect();
+
+            let ok = match op {
+                Op::Execute { action, .. } => {
+                    faucet.execute(&calldata(&identity, &action)).is_ok()
+                }
+                Op::Click { block_height, .. } => faucet.click(identity.clone(), block_height, None).is_ok(),
+                Op::BuyPowerup { name, .. } => faucet.buy_powerup(identity.clone(), &name).is_ok(),
+                Op::Cashout { amount, .. } => faucet.cashout(identity.clone(), amount).is_ok(),
+            };
+
+            let after = snapshot(&faucet, &identity);
+            for (i, p) in PLAYERS.iter().enumerate() {
+                let points = snapshot(&faucet, &Identity(p.to_string())).0;
+                earned[i] += points.saturating_sub(points_before[i]);
+            }
+
+            // Only a prestige, which raises the level, resets the multiplier.
+            if after.2 > before.2 {
+                prop_assert_eq!(after.2, before.2 + 1);
+                prop_assert_eq!(after.1, 1, "prestige kept the multiplier");
+            } else {
+                prop_assert!(after.1 >= before.1, "multiplier decreased: {:?} -> {:?}", before, after);
+            }
+            prop_assert!(after.2 as usize <= faucet.prestige_thresholds().len(), "prestige past the last level");
+            prop_assert!(after.0 <= earned[player], "points {} exceed earned {}", after.0, earned[player]);
+            if !ok {
+                prop_assert_eq!(before, after, "failed action changed the player state");
+            }
+        }
+
+        let commitment = faucet.commit();
+        let decoded = Faucet::try_from(commitment.clone()).expect("decoding committed state");
+        prop_assert_eq!(decoded.commit(), commitment);
+    }
+}
+
+#[test]
+fn referrer_is_credited_a_share_of_clicks() {
+    let mut faucet = Faucet::new();
+    let (alice, bob) = (Identity::from("alice@faucet"), Identity::from("bob@faucet"));
+
+    // The referrer has to be a known player.
+    assert!(faucet
+        .register_referrer(bob.clone(), alice.clone())
+        .is_err());
+    faucet.click(alice.clone(), None, None).unwrap();
+    faucet
+        .register_referrer(bob.clone(), alice.clone())
+        .unwrap();
+    assert!(faucet
+        .register_referrer(bob.clone(), alice.clone())
+        .is_err());
+
+    for _ in 0..25 {
+        faucet.click(bob.clone(), None, None).unwrap();
+    }
+
+    let percent = faucet.referral_percent() as u128;
+    let alice_state = faucet.player(&alice).unwrap();
+    assert_eq!(alice_state.referral_points(), 25 * percent / 100);
+    assert_eq!(alice_state.points(), 1 + 25 * percent / 100);
+    assert_eq!(faucet.player(&bob).unwrap().points(), 25);
+}
+
+#[test]
+fn admins_set_the_referral_percent() {
+    let admin = Identity::from("admin@wallet");
+    let (alice, bob) = (Identity::from("alice@faucet"), Identity::from("bob@faucet"));
+    let mut faucet = Faucet::new().with_admins(vec![admin.clone()]);
+    faucet.click(alice.clone(), None, None).unwrap();
+    faucet
+        .register_referrer(bob.clone(), alice.clone())
+        .unwrap();
+
+    assert!(faucet.set_referral_percent(&alice, 50).is_err());
+    assert!(faucet.set_referral_percent(&admin, 101).is_err());
+    faucet.set_referral_percent(&admin, 50).unwrap();
+
+    for _ in 0..4 {
+        faucet.click(bob.clone(), None, None).unwrap();
+    }
+    assert_eq!(faucet.player(&alice).unwrap().referral_points(), 2);
+}
+
+#[test]
+fn clicks_need_work_once_an_admin_sets_a_difficulty() {
+    let admin = Identity::from("admin@wallet");
+    let alice = Identity::from("alice@faucet");
+    let mut faucet = Faucet::new().with_admins(vec![admin.clone()]);
+
+    assert!(faucet.set_click_difficulty(&alice, 8).is_err());
+    faucet.set_click_difficulty(&admin, 8).unwrap();
+
+    let click = |nonce, work| Nonced {
+        action: FaucetAction::Click,
+        nonce,
+        work,
+    };
+    let work = contract1::solve_work(&alice, 1, 8);
+    assert!(contract1::work_bits(&alice, 1, work) >= 8);
+    assert!(faucet.execute(&calldata(&alice, &click(1, work))).is_ok());
+
+    // The work is bound to the nonce.
+    let unsolved = (0..)
+        .find(|w| contract1::work_bits(&alice, 2, *w) < 8)
+        .unwrap();
+    assert!(faucet
+        .execute(&calldata(&alice, &click(2, unsolved)))
+        .is_err());
+}
+
+#[test]
+fn banned_identities_are_rejected_until_unbanned() {
+    let admin = Identity::from("admin@wallet");
+    let alice = Identity::from("alice@faucet");
+    let mut faucet = Faucet::new().with_admins(vec![admin.clone()]);
+    let click = |nonce| Nonced {
+        action: FaucetAction::Click,
+        nonce,
+        work: 0,
+    };
+
+    assert!(faucet.ban(&alice, admin.clone(), "".to_string()).is_err());
+    assert!(matches!(
+        faucet.ban(&admin, admin.clone(), "".to_string()),
+        Err(FaucetError::CannotBanAdmin { .. })
+    ));
+    faucet
+        .ban(&admin, alice.clone(), "bot".to_string())
+        .unwrap();
+    assert!(faucet.execute(&calldata(&alice, &click(1))).is_err());
+
+    faucet.unban(&admin, alice.clone()).unwrap();
+    assert!(faucet.execute(&calldata(&alice, &click(2))).is_ok());
+}
+
+#[test]
+fn ending_a_season_commits_standings_and_carries_points_over() {
+    let admin = Identity::from("admin@wallet");
+    let alice = Identity::from("alice@faucet");
+    let mut faucet = Faucet::new().with_admins(vec![admin.clone()]);
+    for _ in 0..10 {
+        faucet.click(alice.clone(), None, None).unwrap();
+    }
+    let standings = faucet.standings();
+
+    assert!(faucet.end_season(&admin, 101).is_err());
+    faucet.end_season(&admin, 20).unwrap();
+
+    assert_eq!(faucet.season(), 1);
+    assert_eq!(
+        faucet.season_standings(),
+        &[contract1::standings_hash(&standings)]
+    );
+    assert_eq!(faucet.player(&alice).unwrap().points(), 2);
+}
+
+#[test]
+fn prestige_trades_points_for_a_compounding_multiplier() {
+    let alice = Identity::from("alice@faucet");
+    let mut faucet = Faucet::new();
+    let threshold = faucet.prestige_threshold(0).unwrap();
+
+    faucet.click(alice.clone(), None, None).unwrap();
+    assert!(faucet.prestige(alice.clone()).is_err());
+
+    while faucet.player(&alice).unwrap().points() < threshold {
+        faucet.click(alice.clone(), None, None).unwrap();
+    }
+    faucet.prestige(alice.clone()).unwrap();
+
+    let player = faucet.player(&alice).unwrap();
+    assert_eq!(player.prestige(), 1);
+    assert_eq!(player.points(), 0);
+    assert!(player.owned_powerups().is_empty());
+
+    faucet.click(alice.clone(), None, None).unwrap();
+    assert_eq!(
+        faucet.player(&alice).unwrap().points(),
+        faucet.prestige_multiplier(1)
+    );
+}
+
+#[test]
+fn admins_set_the_prestige_levels() {
+    let admin = Identity::from("admin@wallet");
+    let alice = Identity::from("alice@faucet");
+    let mut faucet = Faucet::new().with_admins(vec![admin.clone()]);
+
+    assert!(faucet.set_prestige(&alice, vec![1], 3).is_err());
+    assert!(faucet.set_prestige(&admin, vec![1], 0).is_err());
+    faucet.set_prestige(&admin, vec![1], 3).unwrap();
+
+    faucet.click(alice.clone(), None, None).unwrap();
+    faucet.prestige(alice.clone()).unwrap();
+    faucet.click(alice.clone(), None, None).unwrap();
+    assert_eq!(faucet.player(&alice).unwrap().points(), 3);
+
+    // Past the last level, the multiplier stops growing.
+    assert!(faucet.prestige(alice.clone()).is_err());
+    assert_eq!(faucet.player(&alice).unwrap().prestige(), 1);
+}
+
+#[test]
+fn team_points_follow_members_and_switching_is_limited() {
+    let (alice, bob) = (Identity::from("alice@faucet"), Identity::from("bob@faucet"));
+    let mut faucet = Faucet::new();
+    let cooldown = faucet.team_switch_cooldown_blocks();
+
+    assert!(faucet.join_team(alice.clone(), "".to_string(), 0).is_err());
+    faucet
+        .join_team(alice.clone(), "red".to_string(), 0)
+        .unwrap();
+    faucet.join_team(bob.clone(), "red".to_string(), 0).unwrap();
+    faucet.click(alice.clone(), None, None).unwrap();
+    faucet.click(bob.clone(), None, None).unwrap();
+    assert_eq!(faucet.teams()["red"].points(), 2);
+    assert_eq!(faucet.teams()["red"].members(), 2);
+
+    assert!(faucet
+        .join_team(alice.clone(), "blue".to_string(), cooldown - 1)
+        .is_err());
+    faucet
+        .join_team(alice.clone(), "blue".to_string(), cooldown)
+        .unwrap();
+    faucet.click(alice.clone(), None, None).unwrap();
+
+    assert_eq!(faucet.teams()["red"].members(), 1);
+    assert_eq!(faucet.teams()["red"].points(), 2);
+    assert_eq!(faucet.teams()["blue"].points(), 1);
+    assert_eq!(faucet.player(&alice).unwrap().team(), Some("blue"));
+}
+
+#[test]
+fn daily_streaks_add_a_bonus_to_clicks() {
+    let admin = Identity::from("admin@wallet");
+    let alice = Identity::from("alice@faucet");
+    let mut faucet = Faucet::new().with_admins(vec![admin.clone()]);
+    faucet
+        .set_streak_tiers(&admin, vec![StreakTier::new(2, 100)])
+        .unwrap();
+    assert!(faucet
+        .set_streak_tiers(&admin, vec![StreakTier::new(3, 10), StreakTier::new(2, 20)])
+        .is_err());
+    assert!(faucet.set_streak_tiers(&alice, vec![]).is_err());
+
+    let points = |faucet: &Faucet| faucet.player(&alice).unwrap().points();
+    faucet.click(alice.clone(), None, Some(10)).unwrap();
+    faucet.click(alice.clone(), None, Some(10)).unwrap();
+    assert_eq!(points(&faucet), 2);
+
+    faucet.click(alice.clone(), None, Some(11)).unwrap();
+    assert_eq!(faucet.player(&alice).unwrap().streak(), 2);
+    assert_eq!(points(&faucet), 4);
+
+    // What the next click gets, as reported once the streak may have lapsed.
+    let player = faucet.player(&alice).unwrap();
+    assert_eq!(player.streak_on(11), 2);
+    assert_eq!(player.streak_on(12), 3);
+    assert_eq!(player.streak_on(13), 1);
+
+    // Missing a day restarts the streak.
+    faucet.click(alice.clone(), None, Some(13)).unwrap();
+    assert_eq!(faucet.player(&alice).unwrap().streak(), 1);
+    assert_eq!(points(&faucet), 5);
+}
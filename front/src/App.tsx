@@ -1,6 +1,6 @@
 import { useState, useEffect, useCallback, useRef } from "react";
 import "./App.css";
-import { blob_click } from "./types/faucet";
+import { blob_click, faucetContractName } from "./types/faucet";
 import { nodeService } from "./services/NodeService";
 import { BlobTransaction } from "hyli";
 import { useConfig } from "./hooks/useConfig";
@@ -291,9 +291,9 @@ function App() {
       if (bombPenalty === 0) {
         // Send blob tx
         // const blobTransfer = transfer("faucet", wallet.address, "oranj", BigInt(1), 1);
-        const blobClick = blob_click(0);
+        const identity = `${wallet.address}@${faucetContractName}`;
+        const blobClick = await blob_click(identity);
 
-        const identity = `${wallet.address}@${blobClick.contract_name}`;
         const blobTx: BlobTransaction = {
           identity,
           blobs: [blobClick],
@@ -1,16 +1,55 @@
-import React, { useEffect } from "react";
+import React, { useEffect, useState } from "react";
 
 interface Transaction {
     id: string;
     timestamp: number;
 }
 
+interface TxStatus {
+    state: "submitted" | "sequenced" | "settled" | "failed" | "timed_out";
+    proof: { status: "pending" | "proving" | "verified" | "failed" };
+}
+
+const FINAL_STATES = ["settled", "failed", "timed_out"];
+
+const fetchTxStatus = async (txHash: string): Promise<TxStatus | null> => {
+    const response = await fetch(`${import.meta.env.VITE_SERVER_BASE_URL}/api/tx/${txHash}`);
+    if (!response.ok) {
+        return null;
+    }
+    return response.json();
+};
+
 interface TransactionListProps {
     transactions: Transaction[];
     setTransactions: (callback: (prev: Transaction[]) => Transaction[]) => void;
 }
 
 export const TransactionList: React.FC<TransactionListProps> = ({ transactions, setTransactions }) => {
+    const [statuses, setStatuses] = useState<Record<string, TxStatus>>({});
+
+    useEffect(() => {
+        const pending = transactions.filter((tx) => !FINAL_STATES.includes(statuses[tx.id]?.state ?? ""));
+        if (pending.length === 0) {
+            return;
+        }
+        const timeout = setTimeout(async () => {
+            const fetched = await Promise.all(pending.map((tx) => fetchTxStatus(tx.id).catch(() => null)));
+            setStatuses((prev) => {
+                const next = { ...prev };
+                pending.forEach((tx, i) => {
+                    const status = fetched[i];
+                    if (status) {
+                        next[tx.id] = status;
+                    }
+                });
+                return next;
+            });
+        }, 500);
+
+        return () => clearTimeout(timeout);
+    }, [transactions, statuses]);
+
     useEffect(() => {
         const timeout = setTimeout(() => {
             setTransactions((prev) => prev.filter((tx) => Date.now() - tx.timestamp < 3000));
@@ -51,6 +90,7 @@ export const TransactionList: React.FC<TransactionListProps> = ({ transactions,
                     >
                         <div style={{ fontFamily: "monospace", fontSize: "0.75em" }}>
                             Sent tx {tx.id.slice(0, 6)}...{tx.id.slice(-6)}
+                            {statuses[tx.id] && ` · ${statuses[tx.id].state.replace("_", " ")}`}
                         </div>
                     </div>
                 ))}
@@ -1,16 +1,39 @@
 import { useEffect, useState } from "react";
-import { fetchConfig } from "../services/config";
-import { setfaucetContractName } from "../types/faucet";
+import { fetchConfig, fetchSettings } from "../services/config";
+import { setClickDifficulty, setfaucetContractName } from "../types/faucet";
+
+// Admins can change the settings at any time, so they are refetched while the app is open.
+const SETTINGS_REFRESH_MS = 30_000;
 
 export function useConfig() {
     const [isLoading, setIsLoading] = useState(true);
     const [error, setError] = useState<string | null>(null);
 
     useEffect(() => {
+        let cancelled = false;
+        let refresh: ReturnType<typeof setInterval> | undefined;
+
         const loadConfig = async () => {
             try {
                 const config = await fetchConfig();
                 setfaucetContractName(config.contract_name);
+
+                const loadSettings = async () => {
+                    const settings = await fetchSettings(config);
+                    setClickDifficulty(settings.click_difficulty);
+                };
+                if (config.settings) {
+                    setClickDifficulty(config.settings.click_difficulty);
+                } else {
+                    await loadSettings();
+                }
+                if (cancelled) {
+                    return;
+                }
+                refresh = setInterval(() => {
+                    loadSettings().catch((err) => console.error("Failed to refresh settings", err));
+                }, SETTINGS_REFRESH_MS);
+
                 setIsLoading(false);
             } catch (err) {
                 setError(err instanceof Error ? err.message : "Failed to load configuration");
@@ -19,6 +42,10 @@ export function useConfig() {
         };
 
         loadConfig();
+        return () => {
+            cancelled = true;
+            clearInterval(refresh);
+        };
     }, []);
 
     return { isLoading, error };
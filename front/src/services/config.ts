@@ -1,5 +1,39 @@
+interface Powerup {
+    Multiplier: {
+        name: string;
+        price: number;
+        multiplier_bonus: number;
+    };
+}
+
+interface Settings {
+    powerups: Powerup[];
+    click_difficulty: number;
+    referral_percent: number;
+    prestige_thresholds: number[];
+    prestige_factor: number;
+}
+
+interface RateLimit {
+    name: string;
+    path_prefix: string;
+    burst: number;
+    per_second: number;
+}
+
 interface Config {
     contract_name: string;
+    server_version: string;
+    node_url: string;
+    indexer_url: string;
+    faucet_indexer_path: string;
+    // Null until the faucet is registered.
+    settings: Settings | null;
+    token_contract: string;
+    identity_contract: string;
+    program_id: string;
+    verifier: string;
+    rate_limits: RateLimit[];
 }
 
 export async function fetchConfig(): Promise<Config> {
@@ -9,3 +43,14 @@ export async function fetchConfig(): Promise<Config> {
     }
     return response.json();
 }
+
+// Contract settings admins can change, served by the indexer of the server.
+export async function fetchSettings(config: Config): Promise<Settings> {
+    const response = await fetch(
+        `${import.meta.env.VITE_SERVER_BASE_URL}${config.faucet_indexer_path}/settings`,
+    );
+    if (!response.ok) {
+        throw new Error("Failed to fetch settings");
+    }
+    return response.json();
+}
@@ -21,6 +21,13 @@ export const setfaucetContractName = (name: string) => {
     faucetContractName = name;
 };
 
+// Leading zero bits required of the clicks' proof of work, 0 when disabled.
+export let clickDifficulty = 0;
+
+export const setClickDifficulty = (difficulty: number) => {
+    clickDifficulty = difficulty;
+};
+
 //
 // Types
 //
@@ -28,11 +35,13 @@ export const setfaucetContractName = (name: string) => {
 export type Nonced<T> = {
     action: T;
     nonce: number;
+    work: number;
 };
 export const noncedSchema = (schema: BorshSchema) =>
     BorshSchema.Struct({
         action: schema,
         nonce: BorshSchema.u64,
+        work: BorshSchema.u64,
     });
 
 export type FaucetAction =
@@ -46,16 +55,73 @@ export type FaucetAction =
       }
     | {
           Cashout: {};
+      }
+    | {
+          RegisterReferrer: {
+              referrer: string;
+          };
+      }
+    | {
+          SetClickDifficulty: {
+              difficulty: number;
+          };
+      }
+    | {
+          Ban: {
+              identity: string;
+              reason: string;
+          };
+      }
+    | {
+          Unban: {
+              identity: string;
+          };
+      }
+    | {
+          EndSeason: {
+              carry_over_percent: number;
+          };
+      }
+    | {
+          Prestige: {};
+      }
+    | {
+          JoinTeam: {
+              team: string;
+          };
+      }
+    | {
+          SetStreakTiers: {
+              tiers: StreakTier[];
+          };
+      }
+    | {
+          SetReferralPercent: {
+              percent: number;
+          };
+      }
+    | {
+          SetPrestige: {
+              thresholds: bigint[];
+              factor: number;
+          };
       };
 
+export type StreakTier = {
+    days: number;
+    bonus_percent: number;
+};
+
 //
 // Builders
 //
 
-export const blob_click = (_callee: number): Blob => {
+export const blob_click = async (identity: string): Promise<Blob> => {
+    const nonce = Date.now();
     const action: Nonced<FaucetAction> = {
         action: { Click: {} },
-        nonce: Date.now(),
+        nonce,
+        work: await solveWork(identity, nonce, clickDifficulty),
     };
 
     const structured: StructuredBlobData<Nonced<FaucetAction>> = {
@@ -85,6 +151,41 @@ export const blob_click = (_callee: number): Blob => {
 //   return blob;
 // };
 
+//
+// Proof of work, mirrors `work_bits` in the contract
+//
+
+const u64le = (n: number): Uint8Array => {
+    const bytes = new Uint8Array(8);
+    new DataView(bytes.buffer).setBigUint64(0, BigInt(n), true);
+    return bytes;
+};
+
+const leadingZeroBits = (hash: Uint8Array): number => {
+    let bits = 0;
+    for (const byte of hash) {
+        if (byte === 0) {
+            bits += 8;
+            continue;
+        }
+        return bits + Math.clz32(byte) - 24;
+    }
+    return bits;
+};
+
+export const solveWork = async (identity: string, nonce: number, difficulty: number): Promise<number> => {
+    if (difficulty === 0) return 0;
+    const prefix = new TextEncoder().encode(identity);
+    const data = new Uint8Array(prefix.length + 16);
+    data.set(prefix);
+    data.set(u64le(nonce), prefix.length);
+    for (let work = 0; ; work++) {
+        data.set(u64le(work), prefix.length + 8);
+        const hash = new Uint8Array(await crypto.subtle.digest("SHA-256", data));
+        if (leadingZeroBits(hash) >= difficulty) return work;
+    }
+};
+
 //
 // Serialisation
 //
@@ -102,4 +203,39 @@ const schema = BorshSchema.Enum({
         name: BorshSchema.String,
     }),
     Cashout: BorshSchema.Unit,
+    RegisterReferrer: BorshSchema.Struct({
+        referrer: BorshSchema.String,
+    }),
+    SetClickDifficulty: BorshSchema.Struct({
+        difficulty: BorshSchema.u8,
+    }),
+    Ban: BorshSchema.Struct({
+        identity: BorshSchema.String,
+        reason: BorshSchema.String,
+    }),
+    Unban: BorshSchema.Struct({
+        identity: BorshSchema.String,
+    }),
+    EndSeason: BorshSchema.Struct({
+        carry_over_percent: BorshSchema.u8,
+    }),
+    Prestige: BorshSchema.Unit,
+    JoinTeam: BorshSchema.Struct({
+        team: BorshSchema.String,
+    }),
+    SetStreakTiers: BorshSchema.Struct({
+        tiers: BorshSchema.Vec(
+            BorshSchema.Struct({
+                days: BorshSchema.u32,
+                bonus_percent: BorshSchema.u32,
+            }),
+        ),
+    }),
+    SetReferralPercent: BorshSchema.Struct({
+        percent: BorshSchema.u8,
+    }),
+    SetPrestige: BorshSchema.Struct({
+        thresholds: BorshSchema.Vec(BorshSchema.u128),
+        factor: BorshSchema.u32,
+    }),
 });
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use contract1::FaucetEvent;
+use sdk::{BlockHeight, Identity, TxHash};
+use serde::Serialize;
+
+use crate::indexer::{FaucetTxEvent, FaucetTxOutcome};
+
+/// Points a player must reach for [`Achievement::Points1k`].
+const POINTS_1K: u128 = 1_000;
+/// Rank a player must keep for [`Achievement::TopTenForAWeek`].
+const TOP_RANK: usize = 10;
+const WEEK_MS: u128 = 7 * 24 * 60 * 60 * 1000;
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    serde::Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub enum Achievement {
+    FirstClick,
+    Points1k,
+    FirstPowerup,
+    FirstCashout,
+    FirstPrestige,
+    /// Stayed in the top 10 of the leaderboard for 7 days.
+    TopTenForAWeek,
+}
+
+#[derive(Debug, Clone, Serialize, serde::Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct UnlockedAchievement {
+    pub achievement: Achievement,
+    pub tx_hash: TxHash,
+    pub block_height: BlockHeight,
+    /// Timestamp of the block that settled `tx_hash`.
+    pub unlocked_at: u128,
+}
+
+/// Achievements unlocked by the settled faucet transactions.
+#[derive(
+    Debug, Clone, Default, Serialize, serde::Deserialize, BorshSerialize, BorshDeserialize,
+)]
+pub struct Achievements {
+    unlocked: HashMap<Identity, Vec<UnlockedAchievement>>,
+    /// When each account entered the top 10, while it stays there.
+    top_ten_since: HashMap<Identity, u128>,
+}
+
+impl Achievements {
+    pub fn unlocked(&self, account: &Identity) -> &[UnlockedAchievement] {
+        self.unlocked
+            .get(account)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Accounts whose rank [`Achievements::record`] needs for `event`.
+    pub fn ranked_accounts(&self, event: &FaucetTxEvent) -> Vec<Identity> {
+        let mut accounts: Vec<_> = self.top_ten_since.keys().cloned().collect();
+        if !self.top_ten_since.contains_key(&event.account) {
+            accounts.push(event.account.clone());
+        }
+        accounts
+    }
+
+    /// Unlocks the achievements earned with `event`, given the current `ranks` of the
+    /// [`Achievements::ranked_accounts`].
+    pub fn record(&mut self, event: &FaucetTxEvent, ranks: &HashMap<Identity, usize>) {
+        let mut earned = vec![];
+        match &event.outcome {
+            FaucetTxOutcome::Success(FaucetEvent::Clicked { points, .. }) => {
+                earned.push((event.account.clone(), Achievement::FirstClick));
+                if *points >= POINTS_1K {
+                    earned.push((event.account.clone(), Achievement::Points1k));
+                }
+            }
+            FaucetTxOutcome::Success(FaucetEvent::PowerupBought { .. }) => {
+                earned.push((event.account.clone(), Achievement::FirstPowerup));
+            }
+            FaucetTxOutcome::Success(FaucetEvent::CashedOut { .. }) => {
+                earned.push((event.account.clone(), Achievement::FirstCashout));
+            }
+            FaucetTxOutcome::Success(FaucetEvent::Prestiged { .. }) => {
+                earned.push((event.account.clone(), Achievement::FirstPrestige));
+            }
+            _ => {}
+        }
+
+        // Accounts that left the leaderboard (e.g. banned) have no rank anymore.
+        self.top_ten_since
+            .retain(|account, _| ranks.contains_key(account));
+        for (account, rank) in ranks {
+            if *rank > TOP_RANK {
+                self.top_ten_since.remove(account);
+                continue;
+            }
+            let since = *self
+                .top_ten_since
+                .entry(account.clone())
+                .or_insert(event.timestamp);
+            if event.timestamp.saturating_sub(since) >= WEEK_MS {
+                earned.push((account.clone(), Achievement::TopTenForAWeek));
+            }
+        }
+
+        for (account, achievement) in earned {
+            let unlocked = self.unlocked.entry(account).or_default();
+            if unlocked.iter().all(|u| u.achievement != achievement) {
+                unlocked.push(UnlockedAchievement {
+                    achievement,
+                    tx_hash: event.tx_hash.clone(),
+                    block_height: event.block_height,
+                    unlocked_at: event.timestamp,
+                });
+            }
+        }
+    }
+}
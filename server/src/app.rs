@@ -1,37 +1,104 @@
-use std::sync::Arc;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
 use axum::{
-    extract::{Json, State},
-    http::Method,
-    response::IntoResponse,
-    routing::get,
+    body::Body,
+    extract::{Json, Path, Query, Request, State},
+    http::{request::Parts, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
     Router,
 };
-use client_sdk::rest_client::NodeApiHttpClient;
+use client_sdk::{
+    contract_indexer::AppError,
+    rest_client::{NodeApiClient, NodeApiHttpClient},
+};
+use contract1::{Faucet, FaucetAction, FaucetEvent, Nonced, SmtTokenAction};
 use hyle_modules::{
     bus::SharedMessageBus,
     module_handle_messages,
-    modules::{module_bus_client, BuildApiContextInner, Module},
+    modules::{
+        contract_state_indexer::CSIBusEvent, module_bus_client, prover::AutoProverEvent,
+        BuildApiContextInner, Module,
+    },
+    node_state::module::NodeStateEvent,
+};
+
+use hmac::{Hmac, Mac};
+use sdk::{
+    Blob, BlobIndex, BlobTransaction, Block, BlockHeight, ContractName, Identity, ProgramId, TxHash,
 };
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::sync::broadcast;
+use tower::ServiceExt;
+use tracing::warn;
 
-use sdk::ContractName;
-use serde::Serialize;
-use tower_http::cors::{Any, CorsLayer};
+use crate::{
+    audit::{AuditEntry, AuditLog},
+    conf::{AdminConf, Conf, RateLimitGroup},
+    indexer::{FaucetSettings, FaucetTxOutcome, IndexerEvent},
+    init::VERIFIER,
+    metrics::METRICS,
+    readiness::Readiness,
+    tx_status::TxTracker,
+    ws::{ws_handler, LiveUpdate},
+};
 
+/// Drives the server-side features (http routes, live updates, tx tracking) from the bus.
+///
+/// It follows the blocks streamed by the `DAListener`, the transactions decoded by the
+/// contract state indexer, the `AutoProver` results, and the [`AppEvent`]s sent by other modules.
 pub struct AppModule {
     bus: AppModuleBusClient,
+    faucet_cn: ContractName,
+    /// Updates fanned out to websocket clients.
+    live_updates: broadcast::Sender<LiveUpdate>,
+    tx_tracker: TxTracker,
+    node_client: Arc<NodeApiHttpClient>,
+    /// Height of the last block received from the DA.
+    last_block: BlockHeight,
+    readiness: Readiness,
+}
+
+/// Internal events other modules publish on the bus for the app module to act on.
+#[derive(Debug, Clone)]
+pub enum AppEvent {
+    /// A faucet transaction was sent to the node outside of the http routes
+    /// (e.g. by a payout job); its status becomes available on `/api/tx/{hash}`.
+    TxSubmitted(TxHash),
+    /// A message pushed to the websocket clients subscribed to `account`,
+    /// or to every client when `account` is `None`.
+    Notify {
+        account: Option<Identity>,
+        message: String,
+    },
 }
 
 pub struct AppModuleCtx {
     pub api: Arc<BuildApiContextInner>,
     pub node_client: Arc<NodeApiHttpClient>,
     pub faucet_cn: ContractName,
+    pub program_id: ProgramId,
+    pub config: Arc<Conf>,
+    /// Routes of the faucet contract indexer, queried for the live contract settings.
+    pub indexer: Router,
 }
 
 module_bus_client! {
 #[derive(Debug)]
 pub struct AppModuleBusClient {
+    receiver(CSIBusEvent<IndexerEvent>),
+    receiver(NodeStateEvent),
+    receiver(AutoProverEvent<Faucet>),
+    receiver(AppEvent),
 }
 }
 
@@ -39,21 +106,50 @@ impl Module for AppModule {
     type Context = Arc<AppModuleCtx>;
 
     async fn build(bus: SharedMessageBus, ctx: Self::Context) -> Result<Self> {
+        let tx_tracker = TxTracker::default();
+        let readiness = Readiness::new(ctx.config.ready_max_lag_blocks);
         let state = RouterCtx {
             faucet_cn: ctx.faucet_cn.clone(),
+            node_client: ctx.node_client.clone(),
+            last_nonce: Arc::new(AtomicU64::new(0)),
+            tx_tracker: tx_tracker.clone(),
+            readiness: readiness.clone(),
+            config: ctx.config.clone(),
+            program_id: ctx.program_id.clone(),
+            indexer: ctx.indexer.clone(),
         };
-
-        // Créer un middleware CORS
-        let cors = CorsLayer::new()
-            .allow_origin(Any) // Permet toutes les origines (peut être restreint)
-            .allow_methods(vec![Method::GET, Method::POST]) // Permet les méthodes nécessaires
-            .allow_headers(Any); // Permet tous les en-têtes
+        let (live_updates, _) = broadcast::channel(1024);
 
         let api = Router::new()
             .route("/_health", get(health))
+            .route("/_ready", get(ready))
             .route("/api/config", get(get_config))
+            .route("/api/click", post(post_click))
+            .route("/api/buy", post(post_buy))
+            .route("/api/cashout", post(post_cashout))
+            .route("/api/referrer", post(post_referrer))
+            .route("/api/tx/{hash}", get(get_tx_status))
             .with_state(state)
-            .layer(cors); // Appliquer le middleware CORS
+            .merge(
+                Router::new()
+                    .route("/api/ws", get(ws_handler))
+                    .with_state(live_updates.clone()),
+            );
+
+        let admin_ctx = AdminCtx {
+            config: ctx.config.clone(),
+            audit: AuditLog::new(&ctx.config.data_directory)?,
+            live_updates: live_updates.clone(),
+        };
+        let admin = Router::new()
+            .route("/api/admin/audit", get(get_audit_log))
+            .route("/api/admin/notify", post(post_notify))
+            .route_layer(middleware::from_fn_with_state(
+                admin_ctx.clone(),
+                admin_auth,
+            ))
+            .with_state(admin_ctx);
+        let api = api.merge(admin);
 
         if let Ok(mut guard) = ctx.api.router.lock() {
             if let Some(router) = guard.take() {
@@ -62,38 +158,515 @@ impl Module for AppModule {
         }
         let bus = AppModuleBusClient::new_from_bus(bus.new_handle()).await;
 
-        Ok(AppModule { bus })
+        Ok(AppModule {
+            bus,
+            faucet_cn: ctx.faucet_cn.clone(),
+            live_updates,
+            tx_tracker,
+            node_client: ctx.node_client.clone(),
+            last_block: BlockHeight(0),
+            readiness,
+        })
     }
 
     async fn run(&mut self) -> Result<()> {
+        let mut poll_node = tokio::time::interval(Duration::from_secs(5));
+
         module_handle_messages! {
             on_self self,
+            listen<NodeStateEvent> event => {
+                let NodeStateEvent::NewBlock(block) = event;
+                self.handle_block(&block);
+            }
+            listen<CSIBusEvent<IndexerEvent>> event => {
+                self.handle_indexer_event(event.event);
+            }
+            listen<AutoProverEvent<Faucet>> event => {
+                self.handle_prover_event(event);
+            }
+            listen<AppEvent> event => {
+                self.handle_app_event(event);
+            }
+            _ = poll_node.tick() => {
+                self.poll_node().await;
+            }
         };
 
         Ok(())
     }
 }
 
+impl AppModule {
+    fn handle_block(&mut self, block: &Block) {
+        self.last_block = block.block_height;
+        self.tx_tracker.handle_block(block, &self.faucet_cn);
+        self.readiness.set_da_height(block.block_height.0);
+        self.readiness
+            .set_prover_backlog(self.tx_tracker.unproven());
+        self.update_indexer_height();
+    }
+
+    /// The indexer reports every faucet transaction it goes through, so it processed every
+    /// block before the first one settling a transaction it has not reported.
+    fn update_indexer_height(&self) {
+        let height = match self.tx_tracker.first_unindexed() {
+            Some(height) => height.0.saturating_sub(1).min(self.last_block.0),
+            None => self.last_block.0,
+        };
+        METRICS
+            .indexer_lag_blocks
+            .record(self.last_block.0.saturating_sub(height), &[]);
+        self.readiness.set_indexer_height(height);
+    }
+
+    fn handle_indexer_event(&mut self, event: IndexerEvent) {
+        let event = match event {
+            IndexerEvent::Tx(event) => event,
+            IndexerEvent::Skipped { tx_hash } => {
+                self.tx_tracker.indexed(&tx_hash);
+                self.update_indexer_height();
+                return;
+            }
+        };
+        match &event.outcome {
+            FaucetTxOutcome::Success(FaucetEvent::Clicked { .. }) => {
+                METRICS.clicks_settled.add(1, &[])
+            }
+            FaucetTxOutcome::Success(FaucetEvent::PowerupBought { .. }) => {
+                METRICS.powerups_bought.add(1, &[])
+            }
+            FaucetTxOutcome::Success(FaucetEvent::CashedOut { .. }) => METRICS.cashouts.add(1, &[]),
+            FaucetTxOutcome::Success(_) => {}
+            FaucetTxOutcome::Failure(_) => METRICS.failed_txs.add(1, &[]),
+        }
+        METRICS.players.record(event.leaderboard_size as u64, &[]);
+        self.tx_tracker.indexed(&event.tx_hash);
+        self.update_indexer_height();
+
+        self.publish(LiveUpdate::Tx(event));
+    }
+
+    async fn poll_node(&mut self) {
+        match self.node_client.get_block_height().await {
+            Ok(node_height) => {
+                METRICS
+                    .da_lag_blocks
+                    .record(node_height.0.saturating_sub(self.last_block.0), &[]);
+                self.readiness.set_node_height(Some(node_height.0));
+            }
+            Err(e) => {
+                warn!("Failed to fetch the node block height: {:#}", e);
+                self.readiness.set_node_height(None);
+            }
+        }
+    }
+
+    fn handle_prover_event(&mut self, event: AutoProverEvent<Faucet>) {
+        match event {
+            AutoProverEvent::SuccessTx(tx_hash, _) => self.tx_tracker.proving(&tx_hash),
+            AutoProverEvent::FailedTx(tx_hash, reason) => {
+                self.tx_tracker.proof_failed(&tx_hash, reason)
+            }
+        }
+    }
+
+    fn handle_app_event(&mut self, event: AppEvent) {
+        match event {
+            AppEvent::TxSubmitted(tx_hash) => self.tx_tracker.submitted(&tx_hash),
+            AppEvent::Notify { account, message } => {
+                self.publish(LiveUpdate::Notification { account, message })
+            }
+        }
+    }
+
+    fn publish(&self, update: LiveUpdate) {
+        // No websocket client connected is not an error.
+        let _ = self.live_updates.send(update);
+    }
+}
+
 #[derive(Clone)]
 struct RouterCtx {
     pub faucet_cn: ContractName,
+    pub node_client: Arc<NodeApiHttpClient>,
+    pub last_nonce: Arc<AtomicU64>,
+    pub tx_tracker: TxTracker,
+    pub readiness: Readiness,
+    pub config: Arc<Conf>,
+    pub program_id: ProgramId,
+    pub indexer: Router,
+}
+
+impl RouterCtx {
+    fn faucet_indexer_path(&self) -> String {
+        format!("/v1/indexer/contract/{}", self.faucet_cn)
+    }
+
+    /// Contract settings as of the last block the indexer processed, `None` until the
+    /// faucet is registered.
+    async fn settings(&self) -> Option<FaucetSettings> {
+        let request = axum::http::Request::get(format!("{}/settings", self.faucet_indexer_path()))
+            .body(Body::empty())
+            .ok()?;
+        let response = self.indexer.clone().oneshot(request).await.ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .ok()?;
+        serde_json::from_slice(&body).ok()
+    }
+
+    /// Millisecond timestamp like the frontend uses, bumped so that it strictly increases.
+    fn next_nonce(&self) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let previous = self
+            .last_nonce
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+                Some(now.max(last + 1))
+            })
+            .unwrap_or_default();
+        now.max(previous + 1)
+    }
+
+    /// Sends `action` for `player`, followed by `extra_blobs`, and returns the tx hash.
+    async fn send_action(
+        &self,
+        player: &PlayerRequest,
+        action: FaucetAction,
+        extra_blobs: impl FnOnce(BlobIndex) -> Vec<Blob>,
+    ) -> Result<TxHash, AppError> {
+        let identity = player
+            .identity
+            .clone()
+            .unwrap_or_else(|| format!("{}@{}", player.account, self.faucet_cn).into());
+
+        let mut blobs = player.identity_blobs.clone();
+        let faucet_index = BlobIndex(blobs.len());
+        let action = Nonced {
+            action,
+            nonce: player.nonce.unwrap_or_else(|| self.next_nonce()),
+            work: player.work,
+        };
+        blobs.push(action.as_blob(self.faucet_cn.clone()));
+        blobs.extend(extra_blobs(faucet_index));
+
+        let tx_hash = self
+            .node_client
+            .send_tx_blob(BlobTransaction::new(identity, blobs))
+            .await
+            .map_err(|e| AppError(StatusCode::BAD_GATEWAY, e))?;
+        self.tx_tracker.submitted(&tx_hash);
+
+        Ok(tx_hash)
+    }
 }
 
 async fn health() -> impl IntoResponse {
     Json("OK")
 }
 
+/// Fails with 503 until the node is reachable and the DA caught up with it.
+async fn ready(State(ctx): State<RouterCtx>) -> impl IntoResponse {
+    let report = ctx.readiness.report();
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
 // --------------------------------------------------------
 //     Routes
 // --------------------------------------------------------
 
+/// Everything a client needs to build and follow faucet transactions.
 #[derive(Serialize)]
 struct ConfigResponse {
     contract_name: String,
+    server_version: &'static str,
+    node_url: String,
+    indexer_url: String,
+    /// Path of the faucet indexer routes on this server.
+    faucet_indexer_path: String,
+    /// Live contract settings (powerups, click difficulty...), also served on the indexer
+    /// `/settings` route. `None` until the faucet is registered.
+    settings: Option<FaucetSettings>,
+    token_contract: String,
+    identity_contract: String,
+    /// Hex encoded.
+    program_id: String,
+    verifier: &'static str,
+    rate_limits: Vec<RateLimitGroup>,
 }
 
 async fn get_config(State(ctx): State<RouterCtx>) -> impl IntoResponse {
+    let client = &ctx.config.client;
     Json(ConfigResponse {
+        faucet_indexer_path: ctx.faucet_indexer_path(),
+        settings: ctx.settings().await,
         contract_name: ctx.faucet_cn.0,
+        server_version: env!("CARGO_PKG_VERSION"),
+        node_url: client.node_url.clone(),
+        indexer_url: client.indexer_url.clone(),
+        token_contract: ctx.config.contract.token_contract.clone(),
+        identity_contract: client.identity_contract.clone(),
+        program_id: hex::encode(&ctx.program_id.0),
+        verifier: VERIFIER,
+        rate_limits: ctx.config.rate_limit.groups.clone(),
+    })
+}
+
+/// Who a transaction is sent for.
+#[derive(Deserialize)]
+struct PlayerRequest {
+    /// Player account, the transaction identity is `{account}@{faucet}` by default.
+    account: String,
+    /// Identity to send the transaction as, when proven by `identity_blobs`. Rejected on
+    /// cashouts, which are paid to `account`.
+    #[serde(default)]
+    identity: Option<Identity>,
+    /// Identity verification blobs (e.g. `wallet` or `hydentity`), placed before the faucet blob.
+    #[serde(default)]
+    identity_blobs: Vec<Blob>,
+    /// Nonce the proof of work was computed for, picked by the server otherwise.
+    #[serde(default)]
+    nonce: Option<u64>,
+    /// Proof of work of a click, see `contract1::work_bits`.
+    #[serde(default)]
+    work: u64,
+}
+
+#[derive(Deserialize)]
+struct BuyRequest {
+    #[serde(flatten)]
+    player: PlayerRequest,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct CashoutRequest {
+    #[serde(flatten)]
+    player: PlayerRequest,
+    amount: u128,
+}
+
+#[derive(Deserialize)]
+struct ReferrerRequest {
+    #[serde(flatten)]
+    player: PlayerRequest,
+    /// Full identity of the referrer, e.g. `bob@faucet`.
+    referrer: Identity,
+}
+
+#[derive(Serialize)]
+struct TxResponse {
+    tx_hash: TxHash,
+}
+
+async fn post_click(
+    State(ctx): State<RouterCtx>,
+    Json(request): Json<PlayerRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let tx_hash = ctx
+        .send_action(&request, FaucetAction::Click, |_| vec![])
+        .await?;
+    Ok(Json(TxResponse { tx_hash }))
+}
+
+async fn post_buy(
+    State(ctx): State<RouterCtx>,
+    Json(request): Json<BuyRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let action = FaucetAction::BuyPowerup { name: request.name };
+    let tx_hash = ctx.send_action(&request.player, action, |_| vec![]).await?;
+    Ok(Json(TxResponse { tx_hash }))
+}
+
+async fn post_cashout(
+    State(ctx): State<RouterCtx>,
+    Json(request): Json<CashoutRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    // The contract only pays out to the account of `{account}@{faucet}` identities.
+    if let Some(identity) = &request.player.identity {
+        return Err(AppError(
+            StatusCode::BAD_REQUEST,
+            anyhow!(
+                "Cashouts are sent as {}@{}, not as {}",
+                request.player.account,
+                ctx.faucet_cn,
+                identity
+            ),
+        ));
+    }
+    // The faucet pays the player out with a token transfer right after the faucet blob.
+    let transfer = SmtTokenAction::Transfer {
+        sender: ctx.faucet_cn.0.clone().into(),
+        recipient: request.player.account.clone().into(),
+        amount: request.amount,
+    };
+    let tx_hash = ctx
+        .send_action(&request.player, FaucetAction::Cashout, |faucet_index| {
+            vec![transfer.as_blob(
+                ctx.config.contract.token_contract.clone().into(),
+                Some(faucet_index),
+            )]
+        })
+        .await?;
+    Ok(Json(TxResponse { tx_hash }))
+}
+
+async fn post_referrer(
+    State(ctx): State<RouterCtx>,
+    Json(request): Json<ReferrerRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let action = FaucetAction::RegisterReferrer {
+        referrer: request.referrer,
+    };
+    let tx_hash = ctx.send_action(&request.player, action, |_| vec![]).await?;
+    Ok(Json(TxResponse { tx_hash }))
+}
+
+async fn get_tx_status(
+    State(ctx): State<RouterCtx>,
+    Path(hash): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let tx_hash = TxHash(hash);
+    ctx.tx_tracker.get(&tx_hash).map(Json).ok_or_else(|| {
+        AppError(
+            StatusCode::NOT_FOUND,
+            anyhow::anyhow!("Transaction {} not seen by this server", tx_hash),
+        )
     })
 }
+
+// --------------------------------------------------------
+//     Admin routes
+// --------------------------------------------------------
+
+/// Largest body accepted on admin routes, which have to be buffered to check signatures.
+const ADMIN_MAX_BODY_SIZE: usize = 1024 * 1024;
+
+#[derive(Clone)]
+struct AdminCtx {
+    pub config: Arc<Conf>,
+    pub audit: AuditLog,
+    pub live_updates: broadcast::Sender<LiveUpdate>,
+}
+
+/// Authenticates `/api/admin/*` requests and records each of them in the audit log.
+async fn admin_auth(State(ctx): State<AdminCtx>, request: Request, next: Next) -> Response {
+    let (parts, body) = request.into_parts();
+    let method = parts.method.to_string();
+    let path = parts.uri.path().to_string();
+
+    let (principal, response) = match axum::body::to_bytes(body, ADMIN_MAX_BODY_SIZE).await {
+        Err(_) => (None, StatusCode::PAYLOAD_TOO_LARGE.into_response()),
+        Ok(body) => match authenticate(&ctx.config.admin, &parts, &body) {
+            Ok(principal) => {
+                let request = Request::from_parts(parts, Body::from(body));
+                (Some(principal), next.run(request).await)
+            }
+            Err(e) => (None, AppError(StatusCode::UNAUTHORIZED, e).into_response()),
+        },
+    };
+
+    let entry = AuditEntry::new(principal, method, path, response.status().as_u16());
+    if let Err(e) = ctx.audit.record(&entry) {
+        warn!("Failed to write the admin audit log: {:#}", e);
+    }
+    response
+}
+
+/// Returns who sent the request: the name of its API key, or `hmac` for a signed request.
+///
+/// Signed requests carry `x-timestamp` (unix seconds) and `x-signature`, the hex
+/// HMAC-SHA256 of `"{timestamp}\n{METHOD}\n{path and query}\n{body}"`.
+fn authenticate(conf: &AdminConf, parts: &Parts, body: &[u8]) -> Result<String> {
+    let header = |name: &str| parts.headers.get(name).and_then(|v| v.to_str().ok());
+
+    if let Some(key) = header("x-api-key") {
+        return conf
+            .api_keys
+            .iter()
+            .find(|(_, k)| !k.is_empty() && constant_time_eq(k.as_bytes(), key.as_bytes()))
+            .map(|(name, _)| name.clone())
+            .ok_or_else(|| anyhow!("Unknown API key"));
+    }
+
+    let (Some(timestamp), Some(signature)) = (header("x-timestamp"), header("x-signature")) else {
+        bail!("Missing x-api-key or x-signature header");
+    };
+    let Some(secret) = conf.hmac_secret.as_ref().filter(|s| !s.is_empty()) else {
+        bail!("Signed requests are not enabled");
+    };
+
+    let timestamp: u64 = timestamp.parse().context("Invalid x-timestamp header")?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    if now.abs_diff(timestamp) > conf.max_signature_age_secs {
+        bail!("Signature expired");
+    }
+
+    let signature = hex::decode(signature).context("Invalid x-signature header")?;
+    let path = parts
+        .uri
+        .path_and_query()
+        .map(|p| p.as_str())
+        .unwrap_or_else(|| parts.uri.path());
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())?;
+    mac.update(format!("{timestamp}\n{}\n{path}\n", parts.method).as_bytes());
+    mac.update(body);
+    mac.verify_slice(&signature)
+        .map_err(|_| anyhow!("Invalid signature"))?;
+
+    Ok("hmac".to_string())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[derive(Deserialize)]
+struct AuditQuery {
+    #[serde(default = "default_audit_limit")]
+    limit: usize,
+}
+
+fn default_audit_limit() -> usize {
+    100
+}
+
+async fn get_audit_log(
+    State(ctx): State<AdminCtx>,
+    Query(query): Query<AuditQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    ctx.audit
+        .recent(query.limit)
+        .map(Json)
+        .map_err(|e| AppError(StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+#[derive(Deserialize)]
+struct NotifyRequest {
+    /// Account to notify, every connected client when missing.
+    #[serde(default)]
+    account: Option<Identity>,
+    message: String,
+}
+
+/// Pushes a message to the websocket clients.
+async fn post_notify(
+    State(ctx): State<AdminCtx>,
+    Json(request): Json<NotifyRequest>,
+) -> impl IntoResponse {
+    let _ = ctx.live_updates.send(LiveUpdate::Notification {
+        account: request.account,
+        message: request.message,
+    });
+    StatusCode::NO_CONTENT
+}
@@ -0,0 +1,92 @@
+use std::{
+    fs::OpenOptions,
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// File of [`AuditLog`], relative to the data directory.
+pub const AUDIT_LOG_FILE: &str = "admin_audit.jsonl";
+
+/// One admin request, whether it was authorized or not.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp_ms: u128,
+    /// Name of the API key, `hmac` for signed requests, `None` when authentication failed.
+    pub principal: Option<String>,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+}
+
+impl AuditEntry {
+    pub fn new(principal: Option<String>, method: String, path: String, status: u16) -> Self {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        AuditEntry {
+            timestamp_ms,
+            principal,
+            method,
+            path,
+            status,
+        }
+    }
+}
+
+/// Append-only log of the admin requests, one json entry per line.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+    lock: Arc<Mutex<()>>,
+}
+
+impl AuditLog {
+    pub fn new(data_directory: &std::path::Path) -> Result<Self> {
+        std::fs::create_dir_all(data_directory)
+            .with_context(|| format!("Creating {}", data_directory.display()))?;
+        Ok(AuditLog {
+            path: data_directory.join(AUDIT_LOG_FILE),
+            lock: Arc::new(Mutex::new(())),
+        })
+    }
+
+    pub fn record(&self, entry: &AuditEntry) -> Result<()> {
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Audit log lock poisoned"))?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Opening {}", self.path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    /// Latest `limit` entries, oldest first.
+    pub fn recent(&self, limit: usize) -> Result<Vec<AuditEntry>> {
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Audit log lock poisoned"))?;
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e).with_context(|| format!("Opening {}", self.path.display())),
+        };
+        let mut entries = BufReader::new(file)
+            .lines()
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect::<Result<Vec<AuditEntry>>>()?;
+        entries.drain(..entries.len().saturating_sub(limit));
+        Ok(entries)
+    }
+}
@@ -0,0 +1,198 @@
+use std::{collections::HashMap, net::IpAddr, path::PathBuf};
+
+use anyhow::{Context, Result};
+use axum::http::{HeaderName, HeaderValue, Method};
+use config::File;
+use contract1::Faucet;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, Any, CorsLayer};
+
+#[derive(serde::Deserialize, Debug)]
+pub struct Conf {
+    pub id: String,
+    pub log_format: String,
+    pub data_directory: PathBuf,
+    pub rest_server_port: u16,
+    pub rest_server_max_body_size: usize,
+    pub da_read_from: String,
+    /// Node the server sends transactions to. `NODE_URL` takes precedence.
+    pub node_url: String,
+    pub contract_name: String,
+    pub contract: ContractConf,
+    pub buffer_blocks: u32,
+    pub max_txs_per_proof: usize,
+    pub tx_working_window_size: usize,
+    /// `/_ready` fails while the DA is more than this many blocks behind the node, or the
+    /// contract indexer behind the DA.
+    pub ready_max_lag_blocks: u64,
+    pub cors: CorsConf,
+    pub client: ClientConf,
+    pub admin: AdminConf,
+    pub rate_limit: RateLimitConf,
+}
+
+/// Settings of the faucet contract. Only used when registering it, they can't change after.
+#[derive(serde::Deserialize, Debug)]
+pub struct ContractConf {
+    /// Identities allowed to run the contract's admin actions.
+    #[serde(default)]
+    pub admins: Vec<String>,
+    /// Clicks accepted per player in a single block, 0 disables the limit.
+    #[serde(default)]
+    pub max_clicks_per_block: u32,
+    /// Token contract paying out cashouts.
+    pub token_contract: String,
+}
+
+impl ContractConf {
+    /// State the contract is registered with.
+    pub fn initial_state(&self) -> Faucet {
+        Faucet::new()
+            .with_admins(
+                self.admins
+                    .iter()
+                    .map(|admin| admin.clone().into())
+                    .collect(),
+            )
+            .with_max_clicks_per_block(self.max_clicks_per_block)
+            .with_token_contract(self.token_contract.clone())
+    }
+}
+
+/// Token-bucket rate limiting of the http routes, per route group and client IP.
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct RateLimitConf {
+    /// Proxies whose `X-Forwarded-For` header is trusted to carry the client IP.
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Checked in order, a path falls in the first group whose prefix matches.
+    #[serde(default)]
+    pub groups: Vec<RateLimitGroup>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct RateLimitGroup {
+    pub name: String,
+    pub path_prefix: String,
+    /// Requests a client can send at once.
+    pub burst: u32,
+    /// Rate at which a client gets requests back.
+    pub per_second: f64,
+}
+
+/// Credentials accepted on `/api/admin/*`. With neither keys nor secret, admin routes are closed.
+#[derive(serde::Deserialize, Default)]
+pub struct AdminConf {
+    /// API keys by name, sent in the `x-api-key` header. The name is what the audit log records.
+    #[serde(default)]
+    pub api_keys: HashMap<String, String>,
+    /// Shared secret of HMAC-SHA256 signed requests.
+    #[serde(default)]
+    pub hmac_secret: Option<String>,
+    /// Signed requests older (or further in the future) than this are rejected.
+    pub max_signature_age_secs: u64,
+}
+
+/// Only the key names are shown, the config gets logged at startup.
+impl std::fmt::Debug for AdminConf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminConf")
+            .field("api_keys", &self.api_keys.keys().collect::<Vec<_>>())
+            .field(
+                "hmac_secret",
+                &self.hmac_secret.as_ref().map(|_| "<redacted>"),
+            )
+            .field("max_signature_age_secs", &self.max_signature_age_secs)
+            .finish()
+    }
+}
+
+/// What clients are told on `/api/config` to build their transactions.
+#[derive(serde::Deserialize, Debug)]
+pub struct ClientConf {
+    /// Node url reachable by clients, which may differ from the server's `node_url`.
+    pub node_url: String,
+    pub indexer_url: String,
+    /// Contract proving the player identities.
+    pub identity_contract: String,
+}
+
+/// CORS policy applied to every route served by the server. `"*"` allows anything.
+#[derive(serde::Deserialize, Debug)]
+pub struct CorsConf {
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+}
+
+impl CorsConf {
+    pub fn layer(&self) -> Result<CorsLayer> {
+        let origins = if self.allowed_origins.iter().any(|o| o == "*") {
+            AllowOrigin::from(Any)
+        } else {
+            AllowOrigin::list(
+                self.allowed_origins
+                    .iter()
+                    .map(|o| HeaderValue::from_str(o).with_context(|| format!("CORS origin {o}")))
+                    .collect::<Result<Vec<_>>>()?,
+            )
+        };
+        let methods = if self.allowed_methods.iter().any(|m| m == "*") {
+            AllowMethods::from(Any)
+        } else {
+            AllowMethods::list(
+                self.allowed_methods
+                    .iter()
+                    .map(|m| {
+                        Method::from_bytes(m.to_uppercase().as_bytes())
+                            .with_context(|| format!("CORS method {m}"))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            )
+        };
+        let headers = if self.allowed_headers.iter().any(|h| h == "*") {
+            AllowHeaders::from(Any)
+        } else {
+            AllowHeaders::list(
+                self.allowed_headers
+                    .iter()
+                    .map(|h| {
+                        HeaderName::try_from(h.as_str()).with_context(|| format!("CORS header {h}"))
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            )
+        };
+
+        Ok(CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers(headers))
+    }
+}
+
+impl Conf {
+    /// Loads the embedded `config.toml`, overridden by `FAUCET_*` environment variables.
+    ///
+    /// Nested keys use `__`, lists are comma separated:
+    /// `FAUCET_CORS__ALLOWED_ORIGINS=https://faucet.example.org,https://example.org`.
+    pub fn new() -> Result<Self> {
+        let config = config::Config::builder()
+            .add_source(File::from_str(
+                include_str!("../../config.toml"),
+                config::FileFormat::Toml,
+            ))
+            .add_source(
+                config::Environment::with_prefix("FAUCET")
+                    .separator("__")
+                    .list_separator(",")
+                    .with_list_parse_key("cors.allowed_origins")
+                    .with_list_parse_key("cors.allowed_methods")
+                    .with_list_parse_key("cors.allowed_headers")
+                    .with_list_parse_key("rate_limit.trusted_proxies")
+                    .with_list_parse_key("contract.admins")
+                    .try_parsing(true),
+            )
+            .build()?
+            .try_deserialize()?;
+        Ok(config)
+    }
+}
@@ -1,4 +1,8 @@
-use std::str;
+use std::{
+    collections::{HashMap, HashSet},
+    str,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
 
 use anyhow::{anyhow, Result};
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -16,17 +20,301 @@ use client_sdk::{
     },
     transaction_builder::TxExecutorHandler,
 };
-use sdk::{Identity, RegisterContractEffect, StateCommitment};
+use contract1::{
+    Faucet, FaucetAction, FaucetError, FaucetEvent, Nonced, PlayerState, Powerup, StreakTier, Team,
+    DAY_MS,
+};
+use sdk::{
+    BlockHeight, ContractName, Hashed, Identity, RegisterContractEffect, StateCommitment, TxHash,
+};
 use serde::Serialize;
+use tracing::error;
 
-use crate::*;
+use crate::{achievements::Achievements, metrics::METRICS};
 use client_sdk::contract_indexer::axum;
 use client_sdk::contract_indexer::utoipa;
 
 #[derive(Debug, Clone, Serialize, serde::Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct FaucetCustomState {
     pub contract: Faucet,
+    /// Name the faucet is registered under, the suffix of its players' identities.
+    #[serde(default)]
+    pub contract_name: ContractName,
     pub balances: HashMap<Identity, u128>,
+    /// Latest rejection of each account's transactions.
+    #[serde(default)]
+    pub last_errors: HashMap<Identity, FaucetError>,
+    /// Most recent faucet transactions of each account, oldest first.
+    #[serde(default)]
+    pub history: HashMap<Identity, Vec<FaucetTxEvent>>,
+    /// Points each account turned into tokens.
+    #[serde(default)]
+    pub cashed_out: HashMap<Identity, u128>,
+    /// Accounts banned by the contract admins, left out of the public views of the players.
+    #[serde(default)]
+    pub banned: HashSet<Identity>,
+    /// Ban and unban actions, oldest first.
+    #[serde(default)]
+    pub ban_history: Vec<BanRecord>,
+    /// Final standings of the ended seasons, season 1 first.
+    #[serde(default)]
+    pub seasons: Vec<SeasonArchive>,
+    #[serde(default)]
+    pub achievements: Achievements,
+    /// Prestige level of each account, ranking before the balance.
+    #[serde(default)]
+    pub prestige: HashMap<Identity, u32>,
+}
+
+#[derive(Debug, Clone, Serialize, serde::Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct SeasonArchive {
+    pub season: u32,
+    pub tx_hash: TxHash,
+    pub block_height: BlockHeight,
+    pub timestamp: u128,
+    /// Hex encoded, as recorded on-chain.
+    pub standings_hash: String,
+    /// Off-chain leaderboard, best first.
+    pub leaderboard: Vec<(Identity, u128)>,
+    /// On-chain points of every player ordered by identity, see `contract1::standings_hash`.
+    pub points: Vec<(Identity, u128)>,
+}
+
+#[derive(Debug, Clone, Serialize, serde::Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct BanRecord {
+    pub tx_hash: TxHash,
+    pub block_height: BlockHeight,
+    pub timestamp: u128,
+    /// Admin account who sent the action.
+    pub admin: Identity,
+    pub account: Identity,
+    /// `false` when the account was unbanned.
+    pub banned: bool,
+    pub reason: Option<String>,
+}
+
+/// Number of transactions kept per account in [`FaucetCustomState::history`].
+const HISTORY_LEN: usize = 100;
+
+/// A settled faucet transaction, decoded from its program outputs.
+///
+/// Recorded in the account history and published on the bus by the contract state indexer.
+#[derive(Debug, Clone, Serialize, serde::Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct FaucetTxEvent {
+    pub tx_hash: TxHash,
+    pub account: Identity,
+    pub block_height: BlockHeight,
+    pub timestamp: u128,
+    pub outcome: FaucetTxOutcome,
+    /// Off-chain balance of the account once the transaction is applied.
+    pub balance: u128,
+    /// Leaderboard rank of the account once the transaction is applied.
+    pub rank: usize,
+    /// Number of accounts on the leaderboard.
+    pub leaderboard_size: usize,
+    /// Prestige level of the account once the transaction is applied.
+    pub prestige: u32,
+}
+
+/// Published on the bus by the contract state indexer for every faucet transaction it goes
+/// through, so that its progress is known even when there is no outcome to report.
+#[derive(Debug, Clone)]
+pub enum IndexerEvent {
+    Tx(FaucetTxEvent),
+    /// A transaction without faucet outcome: the faucet blob did not fail a failed transaction,
+    /// or its outputs could not be replayed or decoded.
+    Skipped {
+        tx_hash: TxHash,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, serde::Deserialize, BorshSerialize, BorshDeserialize)]
+pub enum FaucetTxOutcome {
+    Success(FaucetEvent),
+    Failure(FaucetError),
+}
+
+impl FaucetCustomState {
+    /// Applies a decoded transaction to the off-chain state, filling in its balance and rank.
+    fn record(&mut self, mut event: FaucetTxEvent) -> FaucetTxEvent {
+        match &event.outcome {
+            FaucetTxOutcome::Success(FaucetEvent::Clicked { .. }) => {
+                self.balances
+                    .entry(event.account.clone())
+                    .and_modify(|balance| {
+                        *balance += 1; // Increment balance for each click
+                    })
+                    .or_insert(1); // Initialize balance if not present
+            }
+            FaucetTxOutcome::Success(FaucetEvent::CashedOut { amount, .. }) => {
+                *self.cashed_out.entry(event.account.clone()).or_default() += amount;
+            }
+            FaucetTxOutcome::Success(FaucetEvent::Banned { identity, reason }) => {
+                let account = self.account_of(identity);
+                self.banned.insert(account.clone());
+                self.ban_history
+                    .push(BanRecord::new(&event, account, true, Some(reason.clone())));
+            }
+            FaucetTxOutcome::Success(FaucetEvent::Unbanned { identity }) => {
+                let account = self.account_of(identity);
+                self.banned.remove(&account);
+                self.ban_history
+                    .push(BanRecord::new(&event, account, false, None));
+            }
+            FaucetTxOutcome::Success(FaucetEvent::Prestiged { level, .. }) => {
+                self.prestige.insert(event.account.clone(), *level);
+            }
+            FaucetTxOutcome::Success(_) => {}
+            FaucetTxOutcome::Failure(error) => {
+                self.last_errors
+                    .insert(event.account.clone(), error.clone());
+            }
+        }
+
+        event.balance = self.balances.get(&event.account).copied().unwrap_or(0);
+        event.prestige = self.prestige_of(&event.account);
+        event.rank = self.rank_of(&event.account);
+        event.leaderboard_size = self
+            .balances
+            .keys()
+            .filter(|account| !self.banned.contains(*account))
+            .count();
+
+        let ranks = self
+            .achievements
+            .ranked_accounts(&event)
+            .into_iter()
+            .filter(|account| !self.banned.contains(account))
+            .filter(|account| self.balances.contains_key(account))
+            .map(|account| {
+                let rank = self.rank_of(&account);
+                (account, rank)
+            })
+            .collect();
+        self.achievements.record(&event, &ranks);
+
+        let history = self.history.entry(event.account.clone()).or_default();
+        history.push(event.clone());
+        if history.len() > HISTORY_LEN {
+            history.drain(..history.len() - HISTORY_LEN);
+        }
+
+        event
+    }
+
+    /// Finds a player by account (`{account}@{faucet}`) or by full identity, unless banned.
+    fn find_player(&self, account: &Identity) -> Option<(Identity, &PlayerState)> {
+        let identity: Identity = format!("{}@{}", account.0, self.contract_name).into();
+        [account.clone(), identity]
+            .into_iter()
+            .find_map(|id| self.contract.player(&id).map(|p| (id, p)))
+            .filter(|(id, _)| !self.is_banned(id))
+    }
+
+    /// Accounts are keyed without the faucet identity suffix.
+    fn account_of(&self, identity: &Identity) -> Identity {
+        let suffix = format!("@{}", self.contract_name);
+        identity
+            .0
+            .strip_suffix(&suffix)
+            .unwrap_or(&identity.0)
+            .into()
+    }
+
+    fn is_banned(&self, identity: &Identity) -> bool {
+        self.banned.contains(&self.account_of(identity))
+    }
+
+    /// Archives the leaderboard of the season that just ended and carries the balances over.
+    fn archive_season(&mut self, event: &FaucetTxEvent, points: Vec<(Identity, u128)>) {
+        let FaucetTxOutcome::Success(FaucetEvent::SeasonEnded {
+            season,
+            standings_hash,
+            carry_over_percent,
+        }) = &event.outcome
+        else {
+            return;
+        };
+
+        let mut leaderboard: Vec<(Identity, u128)> = self
+            .balances
+            .iter()
+            .filter(|(account, _)| !self.banned.contains(*account))
+            .map(|(account, balance)| (account.clone(), *balance))
+            .collect();
+        leaderboard.sort_by(|a, b| {
+            self.score_of(&b.0)
+                .cmp(&self.score_of(&a.0))
+                .then_with(|| a.0.cmp(&b.0))
+        });
+
+        for balance in self.balances.values_mut() {
+            *balance = *balance * *carry_over_percent as u128 / 100;
+        }
+        self.balances.retain(|_, balance| *balance > 0);
+
+        self.seasons.push(SeasonArchive {
+            season: *season,
+            tx_hash: event.tx_hash.clone(),
+            block_height: event.block_height,
+            timestamp: event.timestamp,
+            standings_hash: hex::encode(standings_hash),
+            leaderboard,
+            points,
+        });
+    }
+
+    fn prestige_of(&self, account: &Identity) -> u32 {
+        self.prestige.get(account).copied().unwrap_or(0)
+    }
+
+    /// Leaderboard key of an account: prestige level first, then balance.
+    fn score_of(&self, account: &Identity) -> (u32, u128) {
+        (
+            self.prestige_of(account),
+            self.balances.get(account).copied().unwrap_or(0),
+        )
+    }
+
+    /// Rank of an account, ties sharing the best rank.
+    fn rank_of(&self, account: &Identity) -> usize {
+        let score = self.score_of(account);
+        self.balances
+            .keys()
+            .filter(|other| !self.banned.contains(*other) && self.score_of(other) > score)
+            .count()
+            + 1
+    }
+}
+
+impl BanRecord {
+    fn new(event: &FaucetTxEvent, account: Identity, banned: bool, reason: Option<String>) -> Self {
+        BanRecord {
+            tx_hash: event.tx_hash.clone(),
+            block_height: event.block_height,
+            timestamp: event.timestamp,
+            admin: event.account.clone(),
+            account,
+            banned,
+            reason,
+        }
+    }
+}
+
+fn build_calldata(
+    tx: &sdk::BlobTransaction,
+    index: sdk::BlobIndex,
+    tx_context: sdk::TxContext,
+) -> sdk::Calldata {
+    sdk::Calldata {
+        identity: tx.identity.clone(),
+        index,
+        blobs: tx.blobs.clone().into(),
+        tx_blob_count: tx.blobs.len(),
+        tx_hash: tx.hashed(),
+        tx_ctx: Some(tx_context),
+        private_input: vec![],
+    }
 }
 
 impl TxExecutorHandler for FaucetCustomState {
@@ -43,26 +331,133 @@ impl TxExecutorHandler for FaucetCustomState {
     }
 
     fn construct_state(
-        _register_blob: &RegisterContractEffect,
-        _metadata: &Option<Vec<u8>>,
+        register_blob: &RegisterContractEffect,
+        metadata: &Option<Vec<u8>>,
     ) -> anyhow::Result<Self> {
         // Inclure le fichier JSON au moment de la compilation
         const INITIAL_STATE: &str = include_str!("./testnet_dump.json");
 
         // Parser le contenu JSON
-        let state: FaucetCustomState = serde_json::from_str(INITIAL_STATE)
+        let mut state: FaucetCustomState = serde_json::from_str(INITIAL_STATE)
             .map_err(|e| anyhow!("Failed to parse testnet_dump.json: {}", e))?;
 
+        // Only the off-chain balances are carried over, the contract replica follows the chain.
+        state.contract = Faucet::construct_state(register_blob, metadata)?;
+        state.contract_name = register_blob.contract_name.clone();
+
         Ok(state)
     }
 }
 
-impl ContractHandler for FaucetCustomState {
+impl FaucetCustomState {
+    fn index_success(
+        &mut self,
+        tx: &sdk::BlobTransaction,
+        index: sdk::BlobIndex,
+        tx_context: sdk::TxContext,
+    ) -> Result<Option<FaucetTxEvent>> {
+        let (block_height, timestamp) = (tx_context.block_height, tx_context.timestamp.0);
+        let calldata = build_calldata(tx, index, tx_context);
+        // Seasons are archived with the points they ended with, before the contract resets them.
+        let standings = matches!(
+            sdk::utils::parse_calldata::<Nonced<FaucetAction>>(&calldata),
+            Ok((
+                Nonced {
+                    action: FaucetAction::EndSeason { .. },
+                    ..
+                },
+                _
+            ))
+        )
+        .then(|| self.contract.standings());
+        let hyle_output = self.contract.handle(&calldata)?;
+
+        let Some(event) = FaucetEvent::from_program_outputs(&hyle_output.program_outputs) else {
+            return Ok(None);
+        };
+        let event = FaucetTxEvent {
+            tx_hash: calldata.tx_hash,
+            account: self.account_of(&tx.identity),
+            block_height,
+            timestamp,
+            outcome: FaucetTxOutcome::Success(event),
+            balance: 0,
+            rank: 0,
+            leaderboard_size: 0,
+            prestige: 0,
+        };
+
+        let event = self.record(event);
+        if let Some(standings) = standings {
+            self.archive_season(&event, standings);
+        }
+        Ok(Some(event))
+    }
+
+    fn index_failure(
+        &mut self,
+        tx: &sdk::BlobTransaction,
+        index: sdk::BlobIndex,
+        tx_context: sdk::TxContext,
+    ) -> Result<Option<FaucetTxEvent>> {
+        // Failed transactions don't change the state: replay on a copy to recover the reason.
+        let (block_height, timestamp) = (tx_context.block_height, tx_context.timestamp.0);
+        let calldata = build_calldata(tx, index, tx_context);
+        let hyle_output = self.contract.clone().handle(&calldata)?;
+
+        let Some(error) = FaucetError::from_program_outputs(&hyle_output.program_outputs) else {
+            return Ok(None);
+        };
+        let event = FaucetTxEvent {
+            tx_hash: calldata.tx_hash,
+            account: self.account_of(&tx.identity),
+            block_height,
+            timestamp,
+            outcome: FaucetTxOutcome::Failure(error),
+            balance: 0,
+            rank: 0,
+            leaderboard_size: 0,
+            prestige: 0,
+        };
+
+        Ok(Some(self.record(event)))
+    }
+
+    /// Reports the outcome of `tx`, or that it was skipped, so that the app module
+    /// knows how far the indexer went.
+    fn indexed(tx: &sdk::BlobTransaction, result: Result<Option<FaucetTxEvent>>) -> IndexerEvent {
+        match result {
+            Ok(Some(event)) => return IndexerEvent::Tx(event),
+            Ok(None) => {}
+            Err(e) => error!(
+                "Failed to index faucet transaction {}: {:#}",
+                tx.hashed(),
+                e
+            ),
+        }
+        IndexerEvent::Skipped {
+            tx_hash: tx.hashed(),
+        }
+    }
+}
+
+impl ContractHandler<IndexerEvent> for FaucetCustomState {
     async fn api(store: ContractHandlerStore<FaucetCustomState>) -> (Router<()>, OpenApi) {
         let (router, api) = OpenApiRouter::default()
             .routes(routes!(get_state))
+            .routes(routes!(get_settings))
             .routes(routes!(get_leaderboard))
             .routes(routes!(get_balance))
+            .routes(routes!(get_last_error))
+            .routes(routes!(get_history))
+            .routes(routes!(get_player))
+            .routes(routes!(get_referrals))
+            .routes(routes!(get_streak))
+            .routes(routes!(get_bans))
+            .routes(routes!(get_season_leaderboard))
+            .routes(routes!(get_achievements))
+            .routes(routes!(get_team_leaderboard))
+            .routes(routes!(get_team))
             .split_for_parts();
 
         (router.with_state(store), api)
@@ -71,17 +466,21 @@ impl ContractHandler for FaucetCustomState {
     fn handle_transaction_success(
         &mut self,
         tx: &sdk::BlobTransaction,
-        _index: sdk::BlobIndex,
-        _tx_context: sdk::TxContext,
-    ) -> Result<Option<()>> {
-        self.balances
-            .entry(tx.identity.clone().0.replace("@faucet", "").into())
-            .and_modify(|balance| {
-                *balance += 1; // Increment balance for each transaction
-            })
-            .or_insert(1); // Initialize balance if not present
+        index: sdk::BlobIndex,
+        tx_context: sdk::TxContext,
+    ) -> Result<Option<IndexerEvent>> {
+        let result = self.index_success(tx, index, tx_context);
+        Ok(Some(Self::indexed(tx, result)))
+    }
 
-        Ok(None)
+    fn handle_transaction_failed(
+        &mut self,
+        tx: &sdk::BlobTransaction,
+        index: sdk::BlobIndex,
+        tx_context: sdk::TxContext,
+    ) -> Result<Option<IndexerEvent>> {
+        let result = self.index_failure(tx, index, tx_context);
+        Ok(Some(Self::indexed(tx, result)))
     }
 }
 
@@ -103,6 +502,46 @@ pub async fn get_state<S: Serialize + Clone + 'static>(
     ))
 }
 
+/// Contract settings clients need to play, as of the last indexed block.
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct FaucetSettings {
+    pub powerups: Vec<Powerup>,
+    /// Leading zero bits required of the clicks' proof of work, 0 when disabled.
+    pub click_difficulty: u8,
+    pub referral_percent: u8,
+    /// Points needed to leave each prestige level.
+    pub prestige_thresholds: Vec<u128>,
+    pub prestige_factor: u32,
+}
+
+#[utoipa::path(
+    get,
+    path = "/settings",
+    tag = "Contract",
+    responses(
+        (status = OK, description = "Get the current contract settings"),
+        (status = NOT_FOUND, description = "Contract not registered yet")
+    )
+)]
+pub async fn get_settings(
+    State(state): State<ContractHandlerStore<FaucetCustomState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let store = state.read().await;
+    let s = store.state.as_ref().ok_or(AppError(
+        StatusCode::NOT_FOUND,
+        anyhow!("No state found for contract '{}'", store.contract_name),
+    ))?;
+    let contract = &s.contract;
+
+    Ok(Json(FaucetSettings {
+        powerups: contract.powerups().to_vec(),
+        click_difficulty: contract.click_difficulty(),
+        referral_percent: contract.referral_percent(),
+        prestige_thresholds: contract.prestige_thresholds().to_vec(),
+        prestige_factor: contract.prestige_factor(),
+    }))
+}
+
 #[utoipa::path(
     get,
     path = "/leaderboard/{account}",
@@ -118,38 +557,63 @@ pub async fn get_leaderboard(
     Path(account): Path<Identity>,
     State(state): State<ContractHandlerStore<FaucetCustomState>>,
 ) -> Result<impl IntoResponse, AppError> {
+    let started = Instant::now();
     let store = state.read().await;
-    let mut leaderboard: Vec<_> = store
+    // Only copy the balances, the rest of the state (history, contract) can be large.
+    let (mut leaderboard, banned): (Vec<(Identity, (u32, u128))>, bool) = store
         .state
-        .clone()
-        .map(|s| s.balances.into_iter().collect())
+        .as_ref()
+        .map(|s| {
+            let leaderboard = s
+                .balances
+                .keys()
+                .filter(|k| !s.banned.contains(*k))
+                .map(|k| (k.clone(), s.score_of(k)))
+                .collect();
+            (leaderboard, s.banned.contains(&account))
+        })
         .unwrap_or_default();
-    leaderboard.sort_by(|a, b| b.1.cmp(&a.1)); // Sort by balance descending
+    leaderboard.sort_by(|a, b| b.1.cmp(&a.1)); // Sort by prestige then balance, descending
 
-    // If account is provided, calculate rank
-    let rank = if !store
-        .state
-        .as_ref()
-        .map(|s| s.balances.contains_key(&account))
-        .unwrap_or(false)
-    {
-        Some(leaderboard.len() + 1) // Account not found, return rank as last position
+    // If account is provided, calculate rank. Banned accounts have none.
+    let rank = if banned {
+        None
     } else {
         leaderboard
             .iter()
             .position(|(identity, _)| identity == &account)
             .map(|pos| pos + 1)
+            .or(Some(leaderboard.len() + 1)) // Account not found, return rank as last position
     };
-    let leaderboard: HashMap<Identity, u128> = leaderboard.into_iter().take(200).collect();
+    let top = &leaderboard[..leaderboard.len().min(200)];
+    let prestige: HashMap<Identity, u32> = top
+        .iter()
+        .filter(|(_, (prestige, _))| *prestige > 0)
+        .map(|(identity, (prestige, _))| (identity.clone(), *prestige))
+        .collect();
+    let leaderboard: HashMap<Identity, u128> = top
+        .iter()
+        .map(|(identity, (_, balance))| (identity.clone(), *balance))
+        .collect();
 
     // Create response with leaderboard and optional rank
     #[derive(serde::Serialize)]
     struct LeaderboardResponse {
         leaderboard: HashMap<Identity, u128>,
+        /// Prestige level of the leaderboard accounts that have one.
+        prestige: HashMap<Identity, u32>,
         rank: Option<usize>,
     }
 
-    let response = LeaderboardResponse { leaderboard, rank };
+    let response = LeaderboardResponse {
+        leaderboard,
+        prestige,
+        rank,
+    };
+
+    METRICS
+        .leaderboard_latency
+        .record(started.elapsed().as_secs_f64(), &[]);
 
     Ok(Json(response))
 }
@@ -172,7 +636,8 @@ pub async fn get_balance(
     let store = state.read().await;
     store
         .state
-        .clone()
+        .as_ref()
+        .filter(|s| !s.is_banned(&account))
         .map(|s| s.balances.get(&account).cloned().unwrap_or(0))
         .map(Json)
         .ok_or(AppError(
@@ -180,3 +645,408 @@ pub async fn get_balance(
             anyhow!("No balance found for account '{}'", account),
         ))
 }
+
+#[utoipa::path(
+    get,
+    path = "/last_error/{account}",
+    params(
+        ("account" = String, Path, description = "Account")
+    ),
+    tag = "Contract",
+    responses(
+        (status = OK, description = "Get the latest rejection of the account's transactions"),
+        (status = NOT_FOUND, description = "No rejected transaction for this account")
+    )
+)]
+pub async fn get_last_error(
+    Path(account): Path<Identity>,
+    State(state): State<ContractHandlerStore<FaucetCustomState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let store = state.read().await;
+    store
+        .state
+        .as_ref()
+        .filter(|s| !s.is_banned(&account))
+        .and_then(|s| s.last_errors.get(&account).cloned())
+        .map(Json)
+        .ok_or(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow!("No error found for account '{}'", account),
+        ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/history/{account}",
+    params(
+        ("account" = String, Path, description = "Account")
+    ),
+    tag = "Contract",
+    responses(
+        (status = OK, description = "Get the latest faucet transactions of the account, oldest first"),
+        (status = NOT_FOUND, description = "Banned account")
+    )
+)]
+pub async fn get_history(
+    Path(account): Path<Identity>,
+    State(state): State<ContractHandlerStore<FaucetCustomState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let store = state.read().await;
+    store
+        .state
+        .as_ref()
+        .filter(|s| !s.is_banned(&account))
+        .map(|s| s.history.get(&account).cloned().unwrap_or_default())
+        .map(Json)
+        .ok_or(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow!("No history found for account '{}'", account),
+        ))
+}
+
+#[derive(Serialize)]
+struct PlayerResponse {
+    identity: Identity,
+    state: PlayerState,
+    /// Leaderboard rank, `None` until the player clicked.
+    rank: Option<usize>,
+    balance: u128,
+    /// Owned powerups and their effects.
+    powerups: Vec<Powerup>,
+    cashed_out: u128,
+    /// Points that can still be cashed out for tokens.
+    unpaid_cashout: u128,
+}
+
+#[utoipa::path(
+    get,
+    path = "/player/{account}",
+    params(
+        ("account" = String, Path, description = "Account, or full identity")
+    ),
+    tag = "Contract",
+    responses(
+        (status = OK, description = "Get the on-chain state of the player with its rank and powerups"),
+        (status = NOT_FOUND, description = "Unknown player")
+    )
+)]
+pub async fn get_player(
+    Path(account): Path<Identity>,
+    State(state): State<ContractHandlerStore<FaucetCustomState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let store = state.read().await;
+    let not_found = || {
+        AppError(
+            StatusCode::NOT_FOUND,
+            anyhow!("No player found for account '{}'", account),
+        )
+    };
+    let s = store.state.as_ref().ok_or_else(not_found)?;
+    let (identity, player) = s.find_player(&account).ok_or_else(not_found)?;
+
+    let account = s.account_of(&identity);
+    let balance = s.balances.get(&account).copied();
+    let powerups = player
+        .owned_powerups()
+        .iter()
+        .filter_map(|name| s.contract.powerups().iter().find(|p| p.name() == name))
+        .cloned()
+        .collect();
+
+    Ok(Json(PlayerResponse {
+        rank: balance.map(|_| s.rank_of(&account)),
+        balance: balance.unwrap_or(0),
+        powerups,
+        cashed_out: s.cashed_out.get(&account).copied().unwrap_or(0),
+        unpaid_cashout: player.points(),
+        identity,
+        state: player.clone(),
+    }))
+}
+
+#[derive(Serialize)]
+struct Referee {
+    identity: Identity,
+    /// Points the referee earned by clicking since it registered its referrer.
+    referred_points: u128,
+    /// Share of those points credited to the referrer.
+    credited: u128,
+}
+
+#[derive(Serialize)]
+struct ReferralsResponse {
+    referrer: Option<Identity>,
+    referral_percent: u8,
+    /// Points credited to the player for its referees' clicks.
+    referral_points: u128,
+    referees: Vec<Referee>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/player/{account}/referrals",
+    params(
+        ("account" = String, Path, description = "Account, or full identity")
+    ),
+    tag = "Contract",
+    responses(
+        (status = OK, description = "Get who referred the player and who it referred"),
+        (status = NOT_FOUND, description = "Unknown player")
+    )
+)]
+pub async fn get_referrals(
+    Path(account): Path<Identity>,
+    State(state): State<ContractHandlerStore<FaucetCustomState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let store = state.read().await;
+    let not_found = || {
+        AppError(
+            StatusCode::NOT_FOUND,
+            anyhow!("No player found for account '{}'", account),
+        )
+    };
+    let s = store.state.as_ref().ok_or_else(not_found)?;
+    let (identity, player) = s.find_player(&account).ok_or_else(not_found)?;
+    let contract = &s.contract;
+
+    let referral_percent = contract.referral_percent();
+    let referees = contract
+        .referees(&identity)
+        .filter(|(identity, _)| !s.is_banned(identity))
+        .map(|(identity, referee)| Referee {
+            identity: identity.clone(),
+            referred_points: referee.referred_points(),
+            credited: referee
+                .referred_points()
+                .saturating_mul(referral_percent as u128)
+                / 100,
+        })
+        .collect();
+
+    Ok(Json(ReferralsResponse {
+        referrer: player.referrer().cloned(),
+        referral_percent,
+        referral_points: player.referral_points(),
+        referees,
+    }))
+}
+
+#[derive(Serialize)]
+struct StreakResponse {
+    /// Consecutive days with a click, as of `last_active_day`.
+    streak: u32,
+    last_active_day: u64,
+    /// Bonus the player's clicks get today: 0 or the first tier's once the streak lapsed.
+    bonus_percent: u32,
+    /// Next tier the player can reach by clicking on the following days.
+    next_tier: Option<StreakTier>,
+    /// Block timestamp, in ms, from which the streak restarts if the player did not click.
+    expires_at: u128,
+}
+
+#[utoipa::path(
+    get,
+    path = "/player/{account}/streak",
+    params(
+        ("account" = String, Path, description = "Account, or full identity")
+    ),
+    tag = "Contract",
+    responses(
+        (status = OK, description = "Get the daily streak of the player and its click bonus"),
+        (status = NOT_FOUND, description = "Unknown player")
+    )
+)]
+pub async fn get_streak(
+    Path(account): Path<Identity>,
+    State(state): State<ContractHandlerStore<FaucetCustomState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let store = state.read().await;
+    let not_found = || {
+        AppError(
+            StatusCode::NOT_FOUND,
+            anyhow!("No player found for account '{}'", account),
+        )
+    };
+    let s = store.state.as_ref().ok_or_else(not_found)?;
+    let (_, player) = s.find_player(&account).ok_or_else(not_found)?;
+    let contract = &s.contract;
+
+    // Streaks follow block time, which the wall clock approximates.
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let streak_today = player.streak_on((now / DAY_MS) as u64);
+
+    Ok(Json(StreakResponse {
+        streak: player.streak(),
+        last_active_day: player.last_active_day(),
+        bonus_percent: contract.streak_bonus_percent(streak_today),
+        next_tier: contract
+            .streak_tiers()
+            .iter()
+            .find(|tier| tier.days > streak_today)
+            .cloned(),
+        expires_at: (player.last_active_day() as u128 + 2) * DAY_MS,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/bans",
+    tag = "Contract",
+    responses(
+        (status = OK, description = "Get the ban and unban actions of the admins, oldest first")
+    )
+)]
+pub async fn get_bans(
+    State(state): State<ContractHandlerStore<FaucetCustomState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let store = state.read().await;
+    Ok(Json(
+        store
+            .state
+            .as_ref()
+            .map(|s| s.ban_history.clone())
+            .unwrap_or_default(),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/seasons/{season}/leaderboard",
+    params(
+        ("season" = u32, Path, description = "Season number, from 1")
+    ),
+    tag = "Contract",
+    responses(
+        (status = OK, description = "Get the final standings of an ended season"),
+        (status = NOT_FOUND, description = "Season not ended yet")
+    )
+)]
+pub async fn get_season_leaderboard(
+    Path(season): Path<u32>,
+    State(state): State<ContractHandlerStore<FaucetCustomState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let store = state.read().await;
+    store
+        .state
+        .as_ref()
+        .and_then(|s| s.seasons.iter().find(|a| a.season == season).cloned())
+        .map(Json)
+        .ok_or(AppError(
+            StatusCode::NOT_FOUND,
+            anyhow!("Season {} has not ended", season),
+        ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/player/{account}/achievements",
+    params(
+        ("account" = String, Path, description = "Account")
+    ),
+    tag = "Contract",
+    responses(
+        (status = OK, description = "Get the achievements unlocked by the account, oldest first")
+    )
+)]
+pub async fn get_achievements(
+    Path(account): Path<Identity>,
+    State(state): State<ContractHandlerStore<FaucetCustomState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let store = state.read().await;
+    Ok(Json(
+        store
+            .state
+            .as_ref()
+            .filter(|s| !s.banned.contains(&account))
+            .map(|s| s.achievements.unlocked(&account).to_vec())
+            .unwrap_or_default(),
+    ))
+}
+
+/// Points and member counts are the on-chain totals: they include banned members and what
+/// they earned while they played, even though those members are no longer listed.
+#[derive(Serialize)]
+struct TeamStanding {
+    name: String,
+    #[serde(flatten)]
+    team: Team,
+}
+
+#[utoipa::path(
+    get,
+    path = "/teams/leaderboard",
+    tag = "Contract",
+    responses(
+        (status = OK, description = "Get the teams, most points first, points of banned members included")
+    )
+)]
+pub async fn get_team_leaderboard(
+    State(state): State<ContractHandlerStore<FaucetCustomState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let store = state.read().await;
+    let mut teams: Vec<TeamStanding> = store
+        .state
+        .as_ref()
+        .map(|s| {
+            s.contract
+                .teams()
+                .iter()
+                .map(|(name, team)| TeamStanding {
+                    name: name.clone(),
+                    team: team.clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    teams.sort_by(|a, b| b.team.points().cmp(&a.team.points()));
+    Ok(Json(teams))
+}
+
+#[derive(Serialize)]
+struct TeamResponse {
+    #[serde(flatten)]
+    standing: TeamStanding,
+    /// Current members and their points, most points first, without the banned ones.
+    members: Vec<(Identity, u128)>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/teams/{name}",
+    params(
+        ("name" = String, Path, description = "Team name")
+    ),
+    tag = "Contract",
+    responses(
+        (status = OK, description = "Get the points and members of a team"),
+        (status = NOT_FOUND, description = "Unknown team")
+    )
+)]
+pub async fn get_team(
+    Path(name): Path<String>,
+    State(state): State<ContractHandlerStore<FaucetCustomState>>,
+) -> Result<impl IntoResponse, AppError> {
+    let store = state.read().await;
+    let not_found = || AppError(StatusCode::NOT_FOUND, anyhow!("No team named '{}'", name));
+    let s = store.state.as_ref().ok_or_else(not_found)?;
+    let team = s.contract.teams().get(&name).ok_or_else(not_found)?;
+
+    let mut members: Vec<_> = s
+        .contract
+        .team_members(&name)
+        .filter(|(identity, _)| !s.is_banned(identity))
+        .map(|(identity, player)| (identity.clone(), player.points()))
+        .collect();
+    members.sort_by(|a, b| b.1.cmp(&a.1));
+
+    Ok(Json(TeamResponse {
+        standing: TeamStanding {
+            name: name.clone(),
+            team: team.clone(),
+        },
+        members,
+    }))
+}
@@ -5,6 +5,9 @@ use sdk::{api::APIRegisterContract, info, ContractName, ProgramId, StateCommitme
 use std::{sync::Arc, time::Duration};
 use tokio::time::timeout;
 
+/// Verifier the faucet contract is registered with.
+pub const VERIFIER: &str = sdk::verifiers::SP1_4;
+
 pub struct ContractInit {
     pub name: ContractName,
     pub program_id: Vec<u8>,
@@ -36,7 +39,7 @@ async fn init_contract(node: &NodeApiHttpClient, contract: ContractInit) -> Resu
         Err(_) => {
             info!("🚀 Registering {} contract", contract.name);
             node.register_contract(APIRegisterContract {
-                verifier: sdk::verifiers::SP1_4.into(),
+                verifier: VERIFIER.into(),
                 program_id: ProgramId(contract.program_id.to_vec()),
                 state_commitment: contract.initial_state,
                 contract_name: contract.name.clone(),
@@ -0,0 +1,12 @@
+pub mod achievements;
+pub mod app;
+pub mod audit;
+pub mod conf;
+pub mod indexer;
+pub mod init;
+pub mod metrics;
+pub mod rate_limit;
+pub mod readiness;
+pub mod rest;
+pub mod tx_status;
+pub mod ws;
@@ -1,8 +1,6 @@
 use anyhow::{Context, Result};
-use app::{AppModule, AppModuleCtx};
 use axum::Router;
 use client_sdk::rest_client::NodeApiHttpClient;
-use config::File;
 use contract1::Faucet;
 use contracts::CONTRACT_ELF;
 use hyle_modules::{
@@ -11,52 +9,32 @@ use hyle_modules::{
         contract_state_indexer::{ContractStateIndexer, ContractStateIndexerCtx},
         da_listener::{DAListener, DAListenerConf},
         prover::{AutoProver, AutoProverCtx},
-        rest::{RestApi, RestApiRunContext},
         BuildApiContextInner, ModulesHandler,
     },
     utils::logger::setup_tracing,
 };
-use indexer::FaucetCustomState;
 use prometheus::Registry;
-use sdk::{api::NodeInfo, info, ContractName, ZkContract};
-use sp1_sdk::{Prover, SP1ProvingKey};
+use sdk::{info, ContractName, ZkContract};
+use server::{
+    app::{AppModule, AppModuleCtx},
+    conf::Conf,
+    indexer::{FaucetCustomState, IndexerEvent},
+    init,
+    rate_limit::RateLimiter,
+    rest::{RestServer, RestServerCtx},
+};
+use sha2::{Digest, Sha256};
+use sp1_sdk::{Prover, SP1ProvingKey, SP1_CIRCUIT_VERSION};
 use std::{
-    collections::HashMap,
     env,
-    path::{Path, PathBuf},
+    path::Path,
     sync::{Arc, Mutex},
 };
-use tracing::error;
-
-mod app;
-mod indexer;
-mod init;
-
-#[derive(serde::Deserialize, Debug)]
-pub struct Conf {
-    pub id: String,
-    pub log_format: String,
-    pub data_directory: PathBuf,
-    pub rest_server_port: u16,
-    pub rest_server_max_body_size: usize,
-    pub da_read_from: String,
-    pub contract_name: String,
-    pub buffer_blocks: u32,
-    pub max_txs_per_proof: usize,
-    pub tx_working_window_size: usize,
-}
+use tracing::{error, warn};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let config: Conf = config::Config::builder()
-        .add_source(File::from_str(
-            include_str!("../../config.toml"),
-            config::FileFormat::Toml,
-        ))
-        .add_source(config::Environment::with_prefix("FAUCET"))
-        .build()
-        .unwrap()
-        .try_deserialize()?;
+    let config = Conf::new().context("reading config")?;
 
     setup_tracing(
         &config.log_format,
@@ -70,17 +48,22 @@ async fn main() -> Result<()> {
 
     info!("Starting app with config: {:?}", &config);
 
-    let node_url = env::var("NODE_URL").unwrap_or_else(|_| "http://localhost:4321".to_string());
+    let node_url = env::var("NODE_URL").unwrap_or_else(|_| config.node_url.clone());
     let node_client = Arc::new(NodeApiHttpClient::new(node_url).context("build node client")?);
 
-    let pk = load_pk(&config.data_directory);
+    let pk = load_pk(&config.data_directory).context("loading proving key")?;
     let prover = client_sdk::helpers::sp1::SP1Prover::new(pk).await;
 
+    let program_id = prover.program_id().expect("getting program id");
+
+    // The prover starts from the registered state, so both must be the same.
+    let initial_state = config.contract.initial_state();
+
     info!("Init contract on node");
     let contracts = vec![init::ContractInit {
         name: contract_name.clone(),
-        program_id: prover.program_id().expect("getting program id").0,
-        initial_state: Faucet::default().commit(),
+        program_id: program_id.0.clone(),
+        initial_state: initial_state.commit(),
     }];
 
     match init::init_node(node_client.clone(), contracts).await {
@@ -114,34 +97,51 @@ async fn main() -> Result<()> {
         openapi: Default::default(),
     });
 
+    // Built on its own router so that the app module can query it.
+    let indexer_api = Arc::new(BuildApiContextInner {
+        router: Mutex::new(Some(Router::new())),
+        openapi: Default::default(),
+    });
+    handler
+        .build_module::<ContractStateIndexer<FaucetCustomState, IndexerEvent>>(
+            ContractStateIndexerCtx {
+                contract_name: contract_name.clone(),
+                data_directory: config.data_directory.clone(),
+                api: indexer_api.clone(),
+            },
+        )
+        .await?;
+    #[allow(clippy::expect_used, reason = "Fail on misconfiguration")]
+    let indexer_router = indexer_api
+        .router
+        .lock()
+        .expect("Context router should be available")
+        .take()
+        .expect("Context router should be available");
+
     let app_ctx = Arc::new(AppModuleCtx {
         api: api.clone(),
         node_client,
         faucet_cn: contract_name.clone(),
+        program_id,
+        config: config.clone(),
+        indexer: indexer_router.clone(),
     });
 
+    handler.build_module::<AppModule>(app_ctx.clone()).await?;
+
     let prover_ctx = Arc::new(AutoProverCtx {
         prover: Arc::new(prover),
         contract_name: contract_name.clone(),
         node: app_ctx.node_client.clone(),
         api: Some(api.clone()),
         data_directory: config.data_directory.clone(),
-        default_state: Default::default(),
+        default_state: initial_state,
         buffer_blocks: config.buffer_blocks,
         max_txs_per_proof: config.max_txs_per_proof,
         tx_working_window_size: config.tx_working_window_size,
     });
 
-    handler.build_module::<AppModule>(app_ctx.clone()).await?;
-
-    handler
-        .build_module::<ContractStateIndexer<FaucetCustomState>>(ContractStateIndexerCtx {
-            contract_name,
-            data_directory: config.data_directory.clone(),
-            api: api.clone(),
-        })
-        .await?;
-
     handler
         .build_module::<AutoProver<Faucet>>(prover_ctx.clone())
         .await?;
@@ -163,20 +163,19 @@ async fn main() -> Result<()> {
         .lock()
         .expect("Context router should be available")
         .take()
-        .expect("Context router should be available");
+        .expect("Context router should be available")
+        .merge(indexer_router);
+
+    // Applied on the final router so that the indexer routes get the same policy as the app ones.
+    let router = RateLimiter::new(&config.rate_limit).layer(router);
+    let router = router.layer(config.cors.layer().context("building CORS layer")?);
 
     handler
-        .build_module::<RestApi>(RestApiRunContext {
+        .build_module::<RestServer>(RestServerCtx {
             port: config.rest_server_port,
             max_body_size: config.rest_server_max_body_size,
             registry,
-            router: router.clone(),
-            openapi: Default::default(),
-            info: NodeInfo {
-                id: config.id.clone(),
-                da_address: config.da_read_from.clone(),
-                pubkey: None,
-            },
+            router,
         })
         .await?;
 
@@ -186,28 +185,63 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-pub fn load_pk(data_directory: &Path) -> SP1ProvingKey {
+/// Proving key cached on disk, tagged with what it was derived from.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CachedProvingKey {
+    elf_hash: String,
+    sp1_version: String,
+    pk: SP1ProvingKey,
+}
+
+/// Identifies the (ELF, SP1 version) pair a proving key was built for.
+fn proving_key_fingerprint() -> (String, String) {
+    (
+        hex::encode(Sha256::digest(CONTRACT_ELF)),
+        SP1_CIRCUIT_VERSION.to_string(),
+    )
+}
+
+pub fn load_pk(data_directory: &Path) -> Result<SP1ProvingKey> {
     let pk_path = data_directory.join("proving_key.bin");
+    let (elf_hash, sp1_version) = proving_key_fingerprint();
 
     if pk_path.exists() {
         info!("Loading proving key from disk");
-        return std::fs::read(&pk_path)
-            .map(|bytes| bincode::deserialize(&bytes).expect("Failed to deserialize proving key"))
-            .expect("Failed to read proving key from disk");
-    } else if let Err(e) = std::fs::create_dir_all(data_directory) {
-        error!("Failed to create data directory: {}", e);
+        let cached = std::fs::read(&pk_path)
+            .context("reading proving key from disk")
+            .and_then(|bytes| {
+                bincode::deserialize::<CachedProvingKey>(&bytes)
+                    .context("deserializing proving key")
+            });
+        match cached {
+            Ok(cached) if cached.elf_hash == elf_hash && cached.sp1_version == sp1_version => {
+                return Ok(cached.pk);
+            }
+            Ok(cached) => warn!(
+                "Cached proving key is stale (elf {} / sp1 {}, expected elf {} / sp1 {}), rebuilding",
+                cached.elf_hash, cached.sp1_version, elf_hash, sp1_version
+            ),
+            Err(e) => warn!("Ignoring unreadable cached proving key: {:#}", e),
+        }
     }
 
+    std::fs::create_dir_all(data_directory).context("creating data directory")?;
+
     info!("Building proving key");
     let client = sp1_sdk::ProverClient::builder().cpu().build();
     let (pk, _) = client.setup(CONTRACT_ELF);
 
-    if let Err(e) = std::fs::write(
-        &pk_path,
-        bincode::serialize(&pk).expect("Failed to serialize proving key"),
-    ) {
-        error!("Failed to save proving key to disk: {}", e);
-    }
+    let cached = CachedProvingKey {
+        elf_hash,
+        sp1_version,
+        pk,
+    };
+    let bytes = bincode::serialize(&cached).context("serializing proving key")?;
+
+    // Write to a temp file first so a crash never leaves a truncated key behind.
+    let tmp_path = pk_path.with_extension("bin.tmp");
+    std::fs::write(&tmp_path, bytes).context("writing proving key to disk")?;
+    std::fs::rename(&tmp_path, &pk_path).context("moving proving key into place")?;
 
-    pk
+    Ok(cached.pk)
 }
@@ -0,0 +1,79 @@
+use std::sync::LazyLock;
+
+use opentelemetry::{
+    global,
+    metrics::{Counter, Gauge, Histogram},
+};
+
+/// Application metrics, exported through the prometheus registry served on `/v1/metrics`.
+pub struct FaucetMetrics {
+    pub clicks_settled: Counter<u64>,
+    pub powerups_bought: Counter<u64>,
+    pub cashouts: Counter<u64>,
+    pub failed_txs: Counter<u64>,
+    pub players: Gauge<u64>,
+    pub da_lag_blocks: Gauge<u64>,
+    pub indexer_lag_blocks: Gauge<u64>,
+    pub proof_batch_size: Histogram<u64>,
+    pub proof_latency: Histogram<f64>,
+    pub failed_proofs: Counter<u64>,
+    pub leaderboard_latency: Histogram<f64>,
+}
+
+/// Instruments are created on first use, once `main` has installed the meter provider.
+pub static METRICS: LazyLock<FaucetMetrics> = LazyLock::new(FaucetMetrics::new);
+
+impl FaucetMetrics {
+    fn new() -> Self {
+        let meter = global::meter("faucet");
+
+        FaucetMetrics {
+            clicks_settled: meter
+                .u64_counter("faucet_clicks_settled")
+                .with_description("Clicks settled on-chain")
+                .build(),
+            powerups_bought: meter
+                .u64_counter("faucet_powerups_bought")
+                .with_description("Powerups bought")
+                .build(),
+            cashouts: meter
+                .u64_counter("faucet_cashouts")
+                .with_description("Cashouts settled on-chain")
+                .build(),
+            failed_txs: meter
+                .u64_counter("faucet_failed_txs")
+                .with_description("Faucet transactions rejected by the contract")
+                .build(),
+            players: meter
+                .u64_gauge("faucet_players")
+                .with_description("Unique players on the leaderboard")
+                .build(),
+            da_lag_blocks: meter
+                .u64_gauge("faucet_da_lag_blocks")
+                .with_description("Blocks between the node and the last block received from the DA")
+                .build(),
+            indexer_lag_blocks: meter
+                .u64_gauge("faucet_indexer_lag_blocks")
+                .with_description("Blocks the contract indexer is behind the DA")
+                .build(),
+            proof_batch_size: meter
+                .u64_histogram("faucet_proof_batch_size")
+                .with_description("Faucet transactions proven by a single proof")
+                .build(),
+            proof_latency: meter
+                .f64_histogram("faucet_proof_latency")
+                .with_description("Time from sequencing a transaction to verifying its proof")
+                .with_unit("s")
+                .build(),
+            failed_proofs: meter
+                .u64_counter("faucet_failed_proofs")
+                .with_description("Transactions the prover failed to execute")
+                .build(),
+            leaderboard_latency: meter
+                .f64_histogram("faucet_leaderboard_request_latency")
+                .with_description("Time spent serving leaderboard requests")
+                .with_unit("s")
+                .build(),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+
+use crate::conf::{RateLimitConf, RateLimitGroup};
+
+/// Past this many tracked clients, the least recently used buckets are evicted.
+pub const MAX_TRACKED_CLIENTS: usize = 100_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token buckets per route group and client IP.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    trusted_proxies: Arc<Vec<IpAddr>>,
+    groups: Arc<Vec<RateLimitGroup>>,
+    buckets: Arc<Mutex<HashMap<(usize, Option<IpAddr>), Bucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new(conf: &RateLimitConf) -> Self {
+        RateLimiter {
+            trusted_proxies: Arc::new(conf.trusted_proxies.clone()),
+            groups: Arc::new(conf.groups.clone()),
+            buckets: Default::default(),
+        }
+    }
+
+    /// Wraps every route of `router`; paths outside of the configured groups are not limited.
+    pub fn layer(self, router: Router) -> Router {
+        router.layer(middleware::from_fn_with_state(self, rate_limit))
+    }
+
+    /// Index of the first group whose prefix matches `path`.
+    pub fn group_of(&self, path: &str) -> Option<usize> {
+        self.groups
+            .iter()
+            .position(|g| path.starts_with(&g.path_prefix))
+    }
+
+    /// Clients currently holding a bucket.
+    pub fn tracked_clients(&self) -> usize {
+        self.buckets.lock().map(|b| b.len()).unwrap_or_default()
+    }
+
+    /// Takes a token from the client's bucket, or returns how long until one is available.
+    pub fn check(
+        &self,
+        group: usize,
+        client: Option<IpAddr>,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let conf = &self.groups[group];
+        let burst = f64::from(conf.burst);
+        let Ok(mut buckets) = self.buckets.lock() else {
+            return Ok(());
+        };
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS {
+            self.evict(&mut buckets, now);
+        }
+
+        let bucket = buckets.entry((group, client)).or_insert(Bucket {
+            tokens: burst,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * conf.per_second).min(burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else if conf.per_second > 0.0 {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / conf.per_second,
+            ))
+        } else {
+            Err(Duration::MAX)
+        }
+    }
+
+    /// Drops the buckets that refilled, then the least recently used ones until half of
+    /// [`MAX_TRACKED_CLIENTS`] is free, so that scans stay rare even when every bucket is in use.
+    fn evict(&self, buckets: &mut HashMap<(usize, Option<IpAddr>), Bucket>, now: Instant) {
+        buckets.retain(|(g, _), b| {
+            let g = &self.groups[*g];
+            b.tokens + now.saturating_duration_since(b.updated).as_secs_f64() * g.per_second
+                < f64::from(g.burst)
+        });
+
+        let excess = buckets.len().saturating_sub(MAX_TRACKED_CLIENTS / 2);
+        if excess > 0 {
+            let mut updated: Vec<Instant> = buckets.values().map(|b| b.updated).collect();
+            let (_, cutoff, _) = updated.select_nth_unstable(excess - 1);
+            let cutoff = *cutoff;
+            buckets.retain(|_, b| b.updated > cutoff);
+        }
+    }
+
+    /// The peer address, or the address it forwarded the request for when it is a trusted proxy.
+    /// Requests without a known peer share a single bucket.
+    ///
+    /// Forwarded addresses are read right to left, skipping our own proxies, so that a client
+    /// can't pick its bucket by prepending addresses.
+    pub fn client_ip(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        let peer = peer?;
+        if !self.trusted_proxies.contains(&peer) {
+            return Some(peer);
+        }
+        Some(self.forwarded_for(headers).unwrap_or(peer))
+    }
+
+    fn forwarded_for(&self, headers: &HeaderMap) -> Option<IpAddr> {
+        let forwarded: Vec<IpAddr> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|ip| ip.trim().parse().ok())
+            .collect();
+        forwarded
+            .into_iter()
+            .rev()
+            .find(|ip| !self.trusted_proxies.contains(ip))
+    }
+}
+
+/// Answers 429 with `Retry-After` once the client exhausted the bucket of the route group.
+///
+/// The client is identified by the peer address the server exposes as `ConnectInfo`;
+/// see [`RateLimiter::client_ip`].
+async fn rate_limit(State(limiter): State<RateLimiter>, request: Request, next: Next) -> Response {
+    let Some(group) = limiter.group_of(request.uri().path()) else {
+        return next.run(request).await;
+    };
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let client = limiter.client_ip(peer, request.headers());
+
+    match limiter.check(group, client, Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            let secs = retry_after.as_secs_f64().ceil().min(u32::MAX as f64) as u64;
+            let mut response = (
+                StatusCode::TOO_MANY_REQUESTS,
+                format!("Too many requests on {}", limiter.groups[group].name),
+            )
+                .into_response();
+            if let Ok(value) = HeaderValue::from_str(&secs.max(1).to_string()) {
+                response.headers_mut().insert(header::RETRY_AFTER, value);
+            }
+            response
+        }
+    }
+}
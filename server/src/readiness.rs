@@ -0,0 +1,74 @@
+use std::sync::{Arc, RwLock};
+
+use serde::Serialize;
+
+/// Sync state of this instance, updated by the app module and served on `/_ready`.
+#[derive(Clone)]
+pub struct Readiness {
+    max_lag_blocks: u64,
+    inner: Arc<RwLock<ReadinessReport>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    /// Whether the last node poll succeeded.
+    pub node_reachable: bool,
+    pub node_height: Option<u64>,
+    /// Height of the last block received from the DA.
+    pub da_height: u64,
+    /// Height of the last block the contract indexer processed.
+    pub indexer_height: u64,
+    /// Faucet transactions sequenced but not proven yet.
+    pub prover_backlog: usize,
+    pub max_lag_blocks: u64,
+}
+
+impl Readiness {
+    pub fn new(max_lag_blocks: u64) -> Self {
+        Readiness {
+            max_lag_blocks,
+            inner: Arc::new(RwLock::new(ReadinessReport {
+                max_lag_blocks,
+                ..Default::default()
+            })),
+        }
+    }
+
+    pub fn report(&self) -> ReadinessReport {
+        self.inner.read().map(|r| r.clone()).unwrap_or_default()
+    }
+
+    pub fn set_da_height(&self, height: u64) {
+        self.update(|r| r.da_height = height);
+    }
+
+    pub fn set_indexer_height(&self, height: u64) {
+        self.update(|r| r.indexer_height = height);
+    }
+
+    pub fn set_prover_backlog(&self, backlog: usize) {
+        self.update(|r| r.prover_backlog = backlog);
+    }
+
+    /// Records the outcome of polling the node, `None` when it could not be reached.
+    pub fn set_node_height(&self, height: Option<u64>) {
+        self.update(|r| {
+            r.node_reachable = height.is_some();
+            if height.is_some() {
+                r.node_height = height;
+            }
+        });
+    }
+
+    fn update(&self, f: impl FnOnce(&mut ReadinessReport)) {
+        if let Ok(mut report) = self.inner.write() {
+            f(&mut report);
+            report.ready = report.node_reachable
+                && report.node_height.is_some_and(|node| {
+                    node.saturating_sub(report.da_height) <= self.max_lag_blocks
+                })
+                && report.da_height.saturating_sub(report.indexer_height) <= self.max_lag_blocks;
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::{future::IntoFuture, net::SocketAddr};
+
+use anyhow::{Context, Result};
+use axum::{
+    extract::{DefaultBodyLimit, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use hyle_modules::{
+    bus::SharedMessageBus,
+    module_handle_messages,
+    modules::{module_bus_client, Module},
+};
+use prometheus::{Encoder, Registry, TextEncoder};
+use tracing::info;
+
+/// Serves the routes the other modules nested, plus `/v1/metrics`.
+///
+/// Used instead of hyle's `RestApi` because it serves the router with the peer address
+/// (`ConnectInfo`), which the rate limiter needs to tell clients apart.
+pub struct RestServer {
+    bus: RestServerBusClient,
+    port: u16,
+    app: Option<Router>,
+}
+
+pub struct RestServerCtx {
+    pub port: u16,
+    pub max_body_size: usize,
+    pub registry: Registry,
+    pub router: Router,
+}
+
+module_bus_client! {
+#[derive(Debug)]
+pub struct RestServerBusClient {
+}
+}
+
+impl Module for RestServer {
+    type Context = RestServerCtx;
+
+    async fn build(bus: SharedMessageBus, ctx: Self::Context) -> Result<Self> {
+        let app = ctx
+            .router
+            .merge(
+                Router::new()
+                    .route("/v1/metrics", get(get_metrics))
+                    .with_state(ctx.registry),
+            )
+            .layer(DefaultBodyLimit::max(ctx.max_body_size));
+
+        Ok(RestServer {
+            bus: RestServerBusClient::new_from_bus(bus.new_handle()).await,
+            port: ctx.port,
+            app: Some(app),
+        })
+    }
+
+    async fn run(&mut self) -> Result<()> {
+        let app = self.app.take().context("REST server already started")?;
+        let listener = tokio::net::TcpListener::bind(("0.0.0.0", self.port))
+            .await
+            .with_context(|| format!("binding port {}", self.port))?;
+        info!("📡  Starting REST server, listening on port {}", self.port);
+
+        // Serves until the modules shut down.
+        let server = tokio::spawn(
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .into_future(),
+        );
+
+        module_handle_messages! {
+            on_self self,
+        };
+
+        server.abort();
+        Ok(())
+    }
+}
+
+async fn get_metrics(State(registry): State<Registry>) -> impl IntoResponse {
+    let mut buffer = Vec::new();
+    match TextEncoder::new().encode(&registry.gather(), &mut buffer) {
+        Ok(()) => (StatusCode::OK, buffer).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
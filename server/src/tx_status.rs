@@ -0,0 +1,237 @@
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::{Arc, RwLock},
+    time::Instant,
+};
+
+use sdk::{Block, BlockHeight, ContractName, TransactionData, TxHash};
+use serde::Serialize;
+
+use crate::metrics::METRICS;
+
+/// Number of transactions remembered before the oldest ones are forgotten.
+const MAX_TRACKED_TXS: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxState {
+    /// Sent to the node by this server, not seen in a block yet.
+    Submitted,
+    Sequenced,
+    Settled,
+    Failed,
+    TimedOut,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ProofStatus {
+    Pending,
+    /// The prover executed the transaction and batched it for proving.
+    Proving,
+    /// A proof for the faucet blob landed on-chain.
+    Verified {
+        proof_tx_hash: TxHash,
+    },
+    /// The prover could not execute the transaction.
+    Failed {
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TxStatus {
+    pub state: TxState,
+    pub sequenced_at: Option<BlockHeight>,
+    pub settled_at: Option<BlockHeight>,
+    pub proof: ProofStatus,
+    /// When this server saw the transaction sequenced, to measure proving latency.
+    #[serde(skip)]
+    sequenced_instant: Option<Instant>,
+    /// Whether the contract indexer went through the transaction.
+    #[serde(skip)]
+    indexed: bool,
+}
+
+impl Default for TxStatus {
+    fn default() -> Self {
+        TxStatus {
+            state: TxState::Submitted,
+            sequenced_at: None,
+            settled_at: None,
+            proof: ProofStatus::Pending,
+            sequenced_instant: None,
+            indexed: false,
+        }
+    }
+}
+
+/// Status of the faucet transactions seen by this server, shared with the http routes.
+#[derive(Clone, Default)]
+pub struct TxTracker {
+    inner: Arc<RwLock<TrackedTxs>>,
+}
+
+#[derive(Default)]
+struct TrackedTxs {
+    statuses: HashMap<TxHash, TxStatus>,
+    order: VecDeque<TxHash>,
+}
+
+impl TrackedTxs {
+    fn entry(&mut self, tx_hash: &TxHash) -> &mut TxStatus {
+        if !self.statuses.contains_key(tx_hash) {
+            self.order.push_back(tx_hash.clone());
+            while self.order.len() > MAX_TRACKED_TXS {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.statuses.remove(&oldest);
+                }
+            }
+        }
+        self.statuses.entry(tx_hash.clone()).or_default()
+    }
+}
+
+impl TxTracker {
+    pub fn get(&self, tx_hash: &TxHash) -> Option<TxStatus> {
+        self.inner.read().ok()?.statuses.get(tx_hash).cloned()
+    }
+
+    /// Number of sequenced faucet transactions still waiting for a proof. Timed out and
+    /// failed transactions never get one.
+    pub fn unproven(&self) -> usize {
+        self.inner
+            .read()
+            .map(|txs| {
+                txs.statuses
+                    .values()
+                    .filter(|status| {
+                        matches!(status.state, TxState::Sequenced | TxState::Settled)
+                            && matches!(status.proof, ProofStatus::Pending | ProofStatus::Proving)
+                    })
+                    .count()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Lowest block settling a faucet transaction the indexer has not gone through yet.
+    pub fn first_unindexed(&self) -> Option<BlockHeight> {
+        self.inner
+            .read()
+            .ok()?
+            .statuses
+            .values()
+            .filter(|status| {
+                !status.indexed && matches!(status.state, TxState::Settled | TxState::Failed)
+            })
+            .filter_map(|status| status.settled_at)
+            .min()
+    }
+
+    /// Records that the indexer went through `tx_hash`, which may happen before this server
+    /// handles the block settling it.
+    ///
+    /// The indexer goes through blocks in order, so the transactions settled before it
+    /// are done too.
+    pub fn indexed(&self, tx_hash: &TxHash) {
+        self.update(|txs| {
+            let status = txs.entry(tx_hash);
+            status.indexed = true;
+            if let Some(settled_at) = status.settled_at {
+                for status in txs.statuses.values_mut() {
+                    if status.settled_at.is_some_and(|h| h < settled_at) {
+                        status.indexed = true;
+                    }
+                }
+            }
+        });
+    }
+
+    pub fn submitted(&self, tx_hash: &TxHash) {
+        self.update(|txs| {
+            txs.entry(tx_hash);
+        });
+    }
+
+    /// Follows the faucet transactions of a block through sequencing, settlement and proving.
+    pub fn handle_block(&self, block: &Block, contract_name: &ContractName) {
+        self.update(|txs| {
+            for (tx_id, tx) in &block.txs {
+                let TransactionData::Blob(blob_tx) = &tx.transaction_data else {
+                    continue;
+                };
+                if blob_tx
+                    .blobs
+                    .iter()
+                    .any(|blob| &blob.contract_name == contract_name)
+                {
+                    let status = txs.entry(&tx_id.1);
+                    status.state = TxState::Sequenced;
+                    status.sequenced_at = Some(block.block_height);
+                    status.sequenced_instant = Some(Instant::now());
+                }
+            }
+
+            // Only transactions sequenced (or submitted) since we started are known.
+            let outcomes = [
+                (&block.successful_txs, TxState::Settled),
+                (&block.failed_txs, TxState::Failed),
+                (&block.timed_out_txs, TxState::TimedOut),
+            ];
+            for (tx_hashes, state) in outcomes {
+                for tx_hash in tx_hashes {
+                    if let Some(status) = txs.statuses.get_mut(tx_hash) {
+                        status.state = state.clone();
+                        status.settled_at = Some(block.block_height);
+                    }
+                }
+            }
+
+            let mut batch_sizes: BTreeMap<&TxHash, u64> = BTreeMap::new();
+            for output in &block.blob_proof_outputs {
+                if &output.contract_name != contract_name {
+                    continue;
+                }
+                *batch_sizes.entry(&output.proof_tx_hash).or_default() += 1;
+                if let Some(status) = txs.statuses.get_mut(&output.blob_tx_hash) {
+                    status.proof = ProofStatus::Verified {
+                        proof_tx_hash: output.proof_tx_hash.clone(),
+                    };
+                    if let Some(sequenced) = status.sequenced_instant.take() {
+                        METRICS
+                            .proof_latency
+                            .record(sequenced.elapsed().as_secs_f64(), &[]);
+                    }
+                }
+            }
+            for batch_size in batch_sizes.into_values() {
+                METRICS.proof_batch_size.record(batch_size, &[]);
+            }
+        });
+    }
+
+    pub fn proving(&self, tx_hash: &TxHash) {
+        self.update(|txs| {
+            if let Some(status) = txs.statuses.get_mut(tx_hash) {
+                if status.proof == ProofStatus::Pending {
+                    status.proof = ProofStatus::Proving;
+                }
+            }
+        });
+    }
+
+    pub fn proof_failed(&self, tx_hash: &TxHash, reason: String) {
+        METRICS.failed_proofs.add(1, &[]);
+        self.update(|txs| {
+            if let Some(status) = txs.statuses.get_mut(tx_hash) {
+                status.proof = ProofStatus::Failed { reason };
+            }
+        });
+    }
+
+    fn update(&self, f: impl FnOnce(&mut TrackedTxs)) {
+        if let Ok(mut txs) = self.inner.write() {
+            f(&mut txs);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::IntoResponse,
+};
+use contract1::FaucetEvent;
+use sdk::Identity;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::indexer::{FaucetTxEvent, FaucetTxOutcome};
+
+/// What the app module fans out to the websocket connections.
+#[derive(Debug, Clone)]
+pub enum LiveUpdate {
+    Tx(FaucetTxEvent),
+    Notification {
+        account: Option<Identity>,
+        message: String,
+    },
+}
+
+/// Messages pushed to websocket clients.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsMessage {
+    /// Any account's balance changed.
+    Score {
+        account: Identity,
+        balance: u128,
+        rank: usize,
+    },
+    /// The subscribed account moved on the leaderboard. Approximate between the account's
+    /// own transactions, see [`Subscription`].
+    RankChanged { account: Identity, rank: usize },
+    /// A transaction of the subscribed account settled.
+    Tx(FaucetTxEvent),
+    /// A message from the server, for everyone or for the subscribed account.
+    Notification { message: String },
+}
+
+#[derive(Deserialize)]
+pub struct WsQuery {
+    /// Account to receive rank changes and transaction settlements for.
+    account: Option<Identity>,
+}
+
+pub async fn ws_handler(
+    ws: WebSocketUpgrade,
+    Query(query): Query<WsQuery>,
+    State(updates): State<broadcast::Sender<LiveUpdate>>,
+) -> impl IntoResponse {
+    let updates = updates.subscribe();
+    ws.on_upgrade(move |socket| push_updates(socket, updates, query.account))
+}
+
+async fn push_updates(
+    mut socket: WebSocket,
+    mut updates: broadcast::Receiver<LiveUpdate>,
+    account: Option<Identity>,
+) {
+    let mut subscription = account.map(Subscription::new);
+    loop {
+        tokio::select! {
+            update = updates.recv() => {
+                let messages = match update {
+                    Ok(LiveUpdate::Tx(event)) => {
+                        let mut messages = score_message(&event).into_iter().collect::<Vec<_>>();
+                        if let Some(subscription) = subscription.as_mut() {
+                            messages.extend(subscription.apply(&event));
+                        }
+                        messages
+                    }
+                    Ok(LiveUpdate::Notification { account, message }) => {
+                        let subscribed = subscription.as_ref().map(|s| &s.account);
+                        if account.is_none() || account.as_ref() == subscribed {
+                            vec![WsMessage::Notification { message }]
+                        } else {
+                            vec![]
+                        }
+                    }
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return,
+                };
+                for message in messages {
+                    let Ok(json) = serde_json::to_string(&message) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(json.into())).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                None | Some(Err(_)) | Some(Ok(Message::Close(_))) => return,
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+}
+
+fn score_message(event: &FaucetTxEvent) -> Option<WsMessage> {
+    matches!(
+        event.outcome,
+        FaucetTxOutcome::Success(FaucetEvent::Clicked { .. })
+    )
+    .then(|| WsMessage::Score {
+        account: event.account.clone(),
+        balance: event.balance,
+        rank: event.rank,
+    })
+}
+
+/// Follows the rank of one account from the stream of settled transactions.
+///
+/// Balance and rank are only known once the account settles a transaction after subscribing,
+/// and are exact whenever it does, as the indexer ranks the account of each transaction. In
+/// between, only the clicks of accounts of the same prestige overtaking it move the rank:
+/// bans, prestiges and season ends of other accounts are missed. Clients needing the exact
+/// rank read it from the indexer's `/leaderboard/{account}`.
+struct Subscription {
+    account: Identity,
+    /// Prestige, balance and rank.
+    known: Option<(u32, u128, usize)>,
+}
+
+impl Subscription {
+    fn new(account: Identity) -> Self {
+        Subscription {
+            account,
+            known: None,
+        }
+    }
+
+    fn apply(&mut self, event: &FaucetTxEvent) -> Vec<WsMessage> {
+        if event.account == self.account {
+            let previous = self
+                .known
+                .replace((event.prestige, event.balance, event.rank));
+            let mut messages = vec![WsMessage::Tx(event.clone())];
+            if previous.map(|(_, _, rank)| rank) != Some(event.rank) {
+                messages.push(WsMessage::RankChanged {
+                    account: self.account.clone(),
+                    rank: event.rank,
+                });
+            }
+            return messages;
+        }
+
+        // Clicks add one to the balance: another account of our prestige leaving our balance
+        // overtakes us.
+        let Some((prestige, balance, rank)) = self.known.as_mut() else {
+            return vec![];
+        };
+        match event.outcome {
+            FaucetTxOutcome::Success(FaucetEvent::Clicked { .. })
+                if event.prestige == *prestige && event.balance == *balance + 1 =>
+            {
+                *rank += 1;
+                vec![WsMessage::RankChanged {
+                    account: self.account.clone(),
+                    rank: *rank,
+                }]
+            }
+            _ => vec![],
+        }
+    }
+}
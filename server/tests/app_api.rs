@@ -0,0 +1,398 @@
+mod common;
+
+use anyhow::Context;
+use borsh::BorshDeserialize;
+use common::{BlockBuilder, TestServer, ADMIN_API_KEY};
+use contract1::{FaucetAction, Nonced, SmtTokenAction};
+use futures_util::StreamExt;
+use sdk::{Blob, BlobData, BlobIndex, BlobTransaction, Hashed, StructuredBlob, TxHash};
+use serde_json::json;
+
+#[derive(serde::Deserialize)]
+struct ReadinessReport {
+    ready: bool,
+    node_reachable: bool,
+    da_height: u64,
+    indexer_height: u64,
+    prover_backlog: usize,
+}
+
+#[tokio::test]
+async fn ready_once_da_caught_up_with_node() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let blocks = BlockBuilder::default()
+        .register_faucet()
+        .settled(vec![("alice@faucet", FaucetAction::Click)])
+        .build();
+
+    let server = TestServer::start(dir.path(), blocks).await?;
+
+    let report: ReadinessReport = server
+        .wait_for("/_ready", |r: &ReadinessReport| {
+            r.ready && r.indexer_height == 2
+        })
+        .await?;
+    assert!(report.node_reachable);
+    assert_eq!(report.da_height, 2);
+    assert_eq!(report.prover_backlog, 0);
+
+    Ok(())
+}
+
+#[derive(serde::Deserialize)]
+struct AuditEntry {
+    principal: Option<String>,
+    path: String,
+    status: u16,
+}
+
+#[tokio::test]
+async fn admin_routes_require_an_api_key_and_are_audited() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let server = TestServer::start(dir.path(), vec![]).await?;
+    let client = reqwest::Client::new();
+    let notify = serde_json::json!({ "message": "hello" });
+
+    let denied = client
+        .post(format!("{}/api/admin/notify", server.url))
+        .json(&notify)
+        .send()
+        .await?;
+    assert_eq!(denied.status(), 401);
+
+    let wrong_key = client
+        .post(format!("{}/api/admin/notify", server.url))
+        .header("x-api-key", "not-the-key")
+        .json(&notify)
+        .send()
+        .await?;
+    assert_eq!(wrong_key.status(), 401);
+
+    let allowed = client
+        .post(format!("{}/api/admin/notify", server.url))
+        .header("x-api-key", ADMIN_API_KEY)
+        .json(&notify)
+        .send()
+        .await?;
+    assert_eq!(allowed.status(), 204);
+
+    let audit: Vec<AuditEntry> = client
+        .get(format!("{}/api/admin/audit", server.url))
+        .header("x-api-key", ADMIN_API_KEY)
+        .send()
+        .await?
+        .json()
+        .await?;
+    let statuses: Vec<_> = audit.iter().map(|e| e.status).collect();
+    assert_eq!(statuses, vec![401, 401, 204]);
+    assert!(audit.iter().all(|e| e.path == "/api/admin/notify"));
+    assert_eq!(audit[2].principal.as_deref(), Some("tests"));
+    assert!(dir.path().join(server::audit::AUDIT_LOG_FILE).exists());
+
+    Ok(())
+}
+
+#[derive(serde::Deserialize)]
+struct ConfigResponse {
+    contract_name: String,
+    token_contract: String,
+    faucet_indexer_path: String,
+    settings: Option<Settings>,
+}
+
+#[derive(serde::Deserialize)]
+struct Settings {
+    powerups: Vec<serde_json::Value>,
+    click_difficulty: u8,
+}
+
+#[tokio::test]
+async fn config_describes_how_to_build_transactions() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let server = TestServer::start(dir.path(), vec![]).await?;
+
+    let config: ConfigResponse = server.get("/api/config").await?.json().await?;
+    assert!(config.settings.is_none());
+    assert_eq!(config.contract_name, common::CONTRACT_NAME);
+    assert_eq!(config.token_contract, "oranj");
+    assert_eq!(
+        config.faucet_indexer_path,
+        common::indexer_path("").trim_end_matches('/')
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn config_includes_the_live_contract_settings() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let blocks = BlockBuilder::default()
+        .with_admins(&["admin@wallet"])
+        .register_faucet()
+        .settled(vec![(
+            "admin@wallet",
+            FaucetAction::SetClickDifficulty { difficulty: 4 },
+        )])
+        .build();
+
+    let server = TestServer::start(dir.path(), blocks).await?;
+
+    let config: ConfigResponse = server
+        .wait_for("/api/config", |c: &ConfigResponse| {
+            c.settings.as_ref().is_some_and(|s| s.click_difficulty == 4)
+        })
+        .await?;
+    assert_eq!(config.settings.map(|s| s.powerups.len()), Some(1));
+
+    Ok(())
+}
+
+#[derive(serde::Deserialize)]
+struct TxResponse {
+    tx_hash: TxHash,
+}
+
+/// Posts `body` to `route` and returns the transaction the node received for it.
+async fn send(
+    server: &TestServer,
+    route: &str,
+    body: serde_json::Value,
+) -> anyhow::Result<BlobTransaction> {
+    let response = server.post(route, &body).await?;
+    anyhow::ensure!(
+        response.status().is_success(),
+        "{route}: {}",
+        response.status()
+    );
+    let response: TxResponse = response.json().await?;
+    let tx = server.last_sent_tx().context("no transaction sent")?;
+    assert_eq!(response.tx_hash, tx.hashed());
+    Ok(tx)
+}
+
+fn structured<P: BorshDeserialize>(tx: &BlobTransaction, index: usize) -> StructuredBlob<P> {
+    sdk::utils::parse_structured_blob(&tx.blobs.clone().into(), &BlobIndex(index))
+        .expect("structured blob")
+}
+
+#[tokio::test]
+async fn action_routes_send_faucet_blobs_to_the_node() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let server = TestServer::start(dir.path(), vec![]).await?;
+
+    let tx = send(
+        &server,
+        "/api/click",
+        json!({ "account": "alice", "nonce": 7, "work": 3 }),
+    )
+    .await?;
+    assert_eq!(tx.identity.0, "alice@faucet");
+    assert_eq!(tx.blobs.len(), 1);
+    let click = structured::<Nonced<FaucetAction>>(&tx, 0);
+    assert_eq!(click.contract_name.0, common::CONTRACT_NAME);
+    assert_eq!(
+        click.data.parameters,
+        Nonced {
+            action: FaucetAction::Click,
+            nonce: 7,
+            work: 3,
+        }
+    );
+
+    let tx = send(
+        &server,
+        "/api/buy",
+        json!({ "account": "alice", "name": "Wooden Click" }),
+    )
+    .await?;
+    let buy = structured::<Nonced<FaucetAction>>(&tx, 0).data.parameters;
+    assert_eq!(
+        buy.action,
+        FaucetAction::BuyPowerup {
+            name: "Wooden Click".to_string()
+        }
+    );
+    // Server picked nonces strictly increase.
+    let next = send(&server, "/api/click", json!({ "account": "alice" })).await?;
+    assert!(
+        structured::<Nonced<FaucetAction>>(&next, 0)
+            .data
+            .parameters
+            .nonce
+            > buy.nonce
+    );
+
+    let tx = send(
+        &server,
+        "/api/cashout",
+        json!({ "account": "alice", "amount": 50 }),
+    )
+    .await?;
+    assert_eq!(tx.identity.0, "alice@faucet");
+    assert_eq!(
+        structured::<Nonced<FaucetAction>>(&tx, 0)
+            .data
+            .parameters
+            .action,
+        FaucetAction::Cashout
+    );
+    let transfer = structured::<SmtTokenAction>(&tx, 1);
+    assert_eq!(transfer.contract_name.0, "oranj");
+    assert_eq!(transfer.data.caller, Some(BlobIndex(0)));
+    let SmtTokenAction::Transfer {
+        sender,
+        recipient,
+        amount,
+    } = transfer.data.parameters
+    else {
+        panic!("cashout must be paid with a transfer");
+    };
+    assert_eq!(
+        (sender.0.as_str(), recipient.0.as_str(), amount),
+        (common::CONTRACT_NAME, "alice", 50)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn identity_blobs_come_before_the_faucet_blob() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let server = TestServer::start(dir.path(), vec![]).await?;
+    let identity_blob = Blob {
+        contract_name: "wallet".into(),
+        data: BlobData(vec![1, 2, 3]),
+    };
+
+    let tx = send(
+        &server,
+        "/api/referrer",
+        json!({
+            "account": "bob",
+            "identity": "bob@wallet",
+            "identity_blobs": [identity_blob],
+            "referrer": "alice@faucet",
+        }),
+    )
+    .await?;
+    assert_eq!(tx.identity.0, "bob@wallet");
+    assert_eq!(tx.blobs[0], identity_blob);
+    assert_eq!(
+        structured::<Nonced<FaucetAction>>(&tx, 1)
+            .data
+            .parameters
+            .action,
+        FaucetAction::RegisterReferrer {
+            referrer: "alice@faucet".into()
+        }
+    );
+
+    // Cashouts are paid to the account, so they can't be sent as another identity.
+    let sent = server.sent_txs.lock().map(|txs| txs.len()).ok();
+    let cashout = server
+        .post(
+            "/api/cashout",
+            &json!({
+                "account": "bob",
+                "identity": "bob@wallet",
+                "identity_blobs": [identity_blob],
+                "amount": 50,
+            }),
+        )
+        .await?;
+    assert_eq!(cashout.status(), reqwest::StatusCode::BAD_REQUEST);
+    assert_eq!(server.sent_txs.lock().map(|txs| txs.len()).ok(), sent);
+
+    Ok(())
+}
+
+#[derive(serde::Deserialize)]
+struct TxStatus {
+    state: String,
+    sequenced_at: Option<u64>,
+    settled_at: Option<u64>,
+    proof: serde_json::Value,
+}
+
+#[tokio::test]
+async fn tx_status_follows_sequencing_settlement_and_proof() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let builder = BlockBuilder::default()
+        .register_faucet()
+        .sequenced(vec![("alice@faucet", FaucetAction::Click)])
+        .proven();
+    let tx_hash = builder.tx_hashes()[0].clone();
+
+    let server = TestServer::start(dir.path(), builder.build()).await?;
+
+    let status: TxStatus = server
+        .wait_for(&format!("/api/tx/{}", tx_hash.0), |s: &TxStatus| {
+            s.state == "settled"
+        })
+        .await?;
+    // Sequenced in block 2, settled by its proof in block 3.
+    assert_eq!((status.sequenced_at, status.settled_at), (Some(2), Some(3)));
+    assert_eq!(status.proof["status"], "verified");
+    assert!(status.proof["proof_tx_hash"].is_string());
+
+    let unknown = server.get("/api/tx/unknown").await?;
+    assert_eq!(unknown.status(), reqwest::StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn ws_pushes_scores_and_the_subscribed_account_updates() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let mut blocks = BlockBuilder::default()
+        .register_faucet()
+        .settled(vec![("alice@faucet", FaucetAction::Click)])
+        .settled(vec![("bob@faucet", FaucetAction::Click)])
+        .settled(vec![("bob@faucet", FaucetAction::Click)])
+        .build();
+    // Genesis and registration first, the clicks once subscribed.
+    let clicks = blocks.split_off(2);
+
+    let server = TestServer::start(dir.path(), blocks).await?;
+    let ws_url = format!("{}/api/ws?account=alice", server.url.replace("http", "ws"));
+    let (mut ws, _) = tokio_tungstenite::connect_async(ws_url).await?;
+    server.stream_blocks(clicks);
+
+    let mut messages = vec![];
+    while !messages.contains(&json!({ "type": "rank_changed", "account": "alice", "rank": 2 })) {
+        let message = tokio::time::timeout(std::time::Duration::from_secs(5), ws.next())
+            .await
+            .context("timed out waiting on the websocket")?
+            .context("websocket closed")??;
+        if let tokio_tungstenite::tungstenite::Message::Text(text) = message {
+            messages.push(serde_json::from_str::<serde_json::Value>(text.as_str())?);
+        }
+    }
+
+    let summary: Vec<(String, String)> = messages
+        .iter()
+        .map(|m| {
+            (
+                m["type"].as_str().unwrap_or_default().to_string(),
+                m["account"].as_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect();
+    let expected = [
+        ("score", "alice"),
+        ("tx", "alice"),
+        ("rank_changed", "alice"),
+        ("score", "bob"),
+        ("score", "bob"),
+        ("rank_changed", "alice"),
+    ];
+    assert_eq!(
+        summary,
+        expected.map(|(t, a)| (t.to_string(), a.to_string()))
+    );
+    assert_eq!(messages[2]["rank"], 1);
+    // Bob's second click overtook alice.
+    assert_eq!(messages[4]["rank"], 1);
+
+    Ok(())
+}
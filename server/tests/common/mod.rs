@@ -0,0 +1,506 @@
+//! Test harness running the server modules against an in-process node stub
+//! and a DA server streaming synthetic blocks to the `DAListener`.
+
+#![allow(dead_code)]
+
+use std::{
+    net::TcpListener,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::{bail, Result};
+use axum::{
+    extract::{Path as AxumPath, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use client_sdk::rest_client::NodeApiHttpClient;
+use contract1::{Faucet, FaucetAction};
+use hyle_modules::{
+    bus::{metrics::BusMetrics, SharedMessageBus},
+    modules::{
+        contract_state_indexer::{ContractStateIndexer, ContractStateIndexerCtx},
+        da_listener::{DAListener, DAListenerConf},
+        data_availability::codec::{
+            DataAvailabilityEvent, DataAvailabilityRequest, DataAvailabilityServer,
+        },
+        BuildApiContextInner, ModulesHandler,
+    },
+};
+use hyle_net::tcp::TcpEvent;
+use prometheus::Registry;
+use sdk::{
+    guest::execute, BlobIndex, BlobProofOutput, BlobTransaction, BlockHeight, Calldata,
+    ConsensusProposal, ContractName, DataProposal, Hashed, Identity, LaneId, ProgramId,
+    ProofDataHash, RegisterContractAction, SignedBlock, TimestampMs, Transaction, TransactionData,
+    TxContext, TxHash, VerifiedProofTransaction, ZkContract,
+};
+use server::{
+    app::{AppModule, AppModuleCtx},
+    conf::Conf,
+    indexer::{FaucetCustomState, IndexerEvent},
+    rest::{RestServer, RestServerCtx},
+};
+use tokio::sync::mpsc;
+
+pub const CONTRACT_NAME: &str = "faucet";
+/// API key the test server accepts on `/api/admin/*`.
+pub const ADMIN_API_KEY: &str = "test-admin-key";
+
+/// Returns a port that was free at the time of the call.
+pub fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|l| l.local_addr())
+        .map(|addr| addr.port())
+        .expect("binding an ephemeral port")
+}
+
+// --------------------------------------------------------
+//     Node stub
+// --------------------------------------------------------
+
+/// Blob transactions received by the node stub, oldest first.
+pub type SentTxs = Arc<Mutex<Vec<BlobTransaction>>>;
+
+/// Serves the handful of node routes the server calls, and returns its base url
+/// with the transactions it receives.
+pub async fn spawn_node_stub() -> (String, SentTxs) {
+    async fn block_height() -> impl IntoResponse {
+        Json(BlockHeight(0))
+    }
+    async fn get_contract(AxumPath(name): AxumPath<String>) -> impl IntoResponse {
+        (StatusCode::NOT_FOUND, format!("Contract {name} not found"))
+    }
+    async fn send_blob(
+        State(sent): State<SentTxs>,
+        Json(tx): Json<BlobTransaction>,
+    ) -> impl IntoResponse {
+        let tx_hash = tx.hashed();
+        sent.lock().expect("sent txs lock").push(tx);
+        Json(tx_hash)
+    }
+
+    let sent = SentTxs::default();
+    let router = Router::new()
+        .route("/v1/da/block/height", get(block_height))
+        .route("/v1/contract/{name}", get(get_contract))
+        .route("/v1/tx/send/blob", post(send_blob))
+        .with_state(sent.clone());
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("binding node stub");
+    let url = format!("http://{}", listener.local_addr().expect("node stub addr"));
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    (url, sent)
+}
+
+// --------------------------------------------------------
+//     Synthetic DA
+// --------------------------------------------------------
+
+/// Program id and verifier the faucet is registered with. Proofs are pre-verified, see
+/// [`BlockBuilder::settled`], so neither is checked.
+pub const PROGRAM_ID: &[u8] = b"faucet-test";
+pub const VERIFIER: &str = "test";
+
+/// Serves `blocks` over the DA protocol on a local port, and returns its address.
+///
+/// Like the node, it streams the blocks from the height the `DAListener` asks for, then
+/// the blocks sent on `more_blocks` as they come.
+pub async fn spawn_fake_da(
+    mut blocks: Vec<SignedBlock>,
+    mut more_blocks: mpsc::UnboundedReceiver<SignedBlock>,
+) -> Result<String> {
+    let port = free_port();
+    let mut server = DataAvailabilityServer::start(port, "FakeDA").await?;
+
+    tokio::spawn(async move {
+        let mut listeners = vec![];
+        loop {
+            tokio::select! {
+                Some(event) = server.listen_next() => {
+                    let TcpEvent::Message {
+                        dest,
+                        data: DataAvailabilityRequest(from),
+                    } = event
+                    else {
+                        continue;
+                    };
+                    for block in blocks.iter().filter(|b| b.height() >= from) {
+                        let event = DataAvailabilityEvent::SignedBlock(block.clone());
+                        if server.send(dest.clone(), event, vec![]).await.is_err() {
+                            break;
+                        }
+                    }
+                    listeners.push(dest);
+                }
+                Some(block) = more_blocks.recv() => {
+                    for dest in &listeners {
+                        let event = DataAvailabilityEvent::SignedBlock(block.clone());
+                        let _ = server.send(dest.clone(), event, vec![]).await;
+                    }
+                    blocks.push(block);
+                }
+                else => break,
+            }
+        }
+    });
+
+    Ok(format!("127.0.0.1:{port}"))
+}
+
+/// Builds the signed blocks of a chain where the faucet is registered and its
+/// transactions get proven, starting from an empty genesis block.
+pub struct BlockBuilder {
+    blocks: Vec<SignedBlock>,
+    /// Faucet state the proofs are computed against.
+    faucet: Faucet,
+    /// Nonces keep increasing across blocks, as the contract rejects replays.
+    nonce: u64,
+    /// Hashes of the faucet transactions, in the order they were added.
+    tx_hashes: Vec<TxHash>,
+    /// Proofs of the transactions sequenced since the last [`BlockBuilder::proven`].
+    pending_proofs: Vec<VerifiedProofTransaction>,
+}
+
+impl Default for BlockBuilder {
+    fn default() -> Self {
+        let mut builder = BlockBuilder {
+            blocks: vec![],
+            // Registered like the server does.
+            faucet: Conf::new()
+                .expect("reading config")
+                .contract
+                .initial_state(),
+            nonce: 0,
+            tx_hashes: vec![],
+            pending_proofs: vec![],
+        };
+        builder.push_block(vec![]);
+        builder
+    }
+}
+
+impl BlockBuilder {
+    /// Identities allowed to run the admin actions, to call before registering the faucet.
+    pub fn with_admins(mut self, admins: &[&str]) -> Self {
+        self.faucet = self
+            .faucet
+            .with_admins(admins.iter().map(|admin| Identity::from(*admin)).collect());
+        self
+    }
+
+    /// Adds a block registering the faucet through the `hyle` contract.
+    pub fn register_faucet(mut self) -> Self {
+        let register = RegisterContractAction {
+            verifier: VERIFIER.into(),
+            program_id: ProgramId(PROGRAM_ID.to_vec()),
+            state_commitment: self.faucet.commit(),
+            contract_name: CONTRACT_NAME.into(),
+            ..Default::default()
+        };
+        let tx = BlobTransaction::new(
+            "hyle@hyle",
+            vec![register.as_blob("hyle".into(), None, None)],
+        );
+        self.push_block(vec![TransactionData::Blob(tx)]);
+        self
+    }
+
+    /// Adds a block with one faucet transaction per `(identity, action)`, each followed
+    /// by the proof of its execution, so that the node state settles them.
+    ///
+    /// The proofs are computed with the height and time of the new block, like the
+    /// indexer replica sees it.
+    pub fn settled(mut self, actions: Vec<(&str, FaucetAction)>) -> Self {
+        let mut txs = vec![];
+        for (identity, action) in actions {
+            let (tx, proof) = self.faucet_tx(identity, action);
+            txs.push(TransactionData::Blob(tx));
+            txs.push(TransactionData::VerifiedProof(proof));
+        }
+        self.push_block(txs);
+        self
+    }
+
+    /// Adds a block with one faucet transaction per `(identity, action)`, settled by
+    /// the next [`BlockBuilder::proven`] block.
+    pub fn sequenced(mut self, actions: Vec<(&str, FaucetAction)>) -> Self {
+        let mut txs = vec![];
+        for (identity, action) in actions {
+            let (tx, proof) = self.faucet_tx(identity, action);
+            txs.push(TransactionData::Blob(tx));
+            self.pending_proofs.push(proof);
+        }
+        self.push_block(txs);
+        self
+    }
+
+    /// Adds a block with the proofs of the transactions sequenced since the last call.
+    pub fn proven(mut self) -> Self {
+        let proofs = std::mem::take(&mut self.pending_proofs)
+            .into_iter()
+            .map(TransactionData::VerifiedProof)
+            .collect();
+        self.push_block(proofs);
+        self
+    }
+
+    /// Hashes of the faucet transactions added so far, oldest first.
+    pub fn tx_hashes(&self) -> &[TxHash] {
+        &self.tx_hashes
+    }
+
+    pub fn build(self) -> Vec<SignedBlock> {
+        self.blocks
+    }
+
+    /// The faucet transaction of `action`, with the next nonce, and its proof.
+    fn faucet_tx(
+        &mut self,
+        identity: &str,
+        action: FaucetAction,
+    ) -> (BlobTransaction, VerifiedProofTransaction) {
+        self.nonce += 1;
+        let tx = faucet_tx(identity, action, self.nonce);
+        self.tx_hashes.push(tx.hashed());
+        let proof = self.prove(&tx);
+        (tx, proof)
+    }
+
+    /// Runs the faucet blob of `tx` like the guest program does, in the next block.
+    fn prove(&mut self, tx: &BlobTransaction) -> VerifiedProofTransaction {
+        let height = self.blocks.len() as u64;
+        let calldata = Calldata {
+            identity: tx.identity.clone(),
+            index: BlobIndex(0),
+            blobs: tx.blobs.clone().into(),
+            tx_blob_count: tx.blobs.len(),
+            tx_hash: tx.hashed(),
+            tx_ctx: Some(TxContext {
+                block_height: BlockHeight(height),
+                timestamp: TimestampMs(GENESIS_TIMESTAMP_MS + height as u128 * 1000),
+                ..Default::default()
+            }),
+            private_input: vec![],
+        };
+        let metadata = borsh::to_vec(&self.faucet).expect("encoding faucet state");
+        let mut hyle_output = execute::<Faucet>(&metadata, &[calldata])
+            .pop()
+            .expect("one output per calldata");
+        if hyle_output.success {
+            self.faucet =
+                Faucet::try_from(hyle_output.next_state.clone()).expect("decoding faucet state");
+        }
+        // Only the height and time are filled in, so the node must not check the
+        // context against the block's.
+        hyle_output.tx_ctx = None;
+
+        VerifiedProofTransaction {
+            contract_name: CONTRACT_NAME.into(),
+            program_id: ProgramId(PROGRAM_ID.to_vec()),
+            verifier: VERIFIER.into(),
+            proof: None,
+            proof_hash: ProofDataHash::default(),
+            proof_size: 0,
+            proven_blobs: vec![BlobProofOutput {
+                blob_tx_hash: tx.hashed(),
+                original_proof_hash: ProofDataHash::default(),
+                hyle_output,
+                program_id: ProgramId(PROGRAM_ID.to_vec()),
+                verifier: VERIFIER.into(),
+            }],
+            is_recursive: false,
+        }
+    }
+
+    fn push_block(&mut self, txs: Vec<TransactionData>) {
+        let height = self.blocks.len() as u64;
+        let txs = txs
+            .into_iter()
+            .map(|transaction_data| Transaction {
+                version: 1,
+                transaction_data,
+            })
+            .collect();
+        let parent_hash = self
+            .blocks
+            .last()
+            .map(|b| b.consensus_proposal.hashed())
+            .unwrap_or_default();
+        self.blocks.push(SignedBlock {
+            data_proposals: vec![(LaneId::default(), vec![DataProposal::new(None, txs)])],
+            consensus_proposal: ConsensusProposal {
+                slot: height,
+                parent_hash,
+                timestamp: TimestampMs(GENESIS_TIMESTAMP_MS + height as u128 * 1000),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+    }
+}
+
+/// Block time of the genesis block; blocks follow every second.
+pub const GENESIS_TIMESTAMP_MS: u128 = 1_750_000_000_000;
+
+pub fn faucet_tx(identity: &str, action: FaucetAction, nonce: u64) -> BlobTransaction {
+    let blob = action.as_blob(ContractName(CONTRACT_NAME.to_string()), nonce);
+    BlobTransaction::new(Identity(identity.to_string()), vec![blob])
+}
+
+// --------------------------------------------------------
+//     Server
+// --------------------------------------------------------
+
+/// A running server: app module, contract indexer, `DAListener` and REST api, reading
+/// blocks from [`spawn_fake_da`].
+pub struct TestServer {
+    pub url: String,
+    /// Transactions the server sent to the node.
+    pub sent_txs: SentTxs,
+    more_blocks: mpsc::UnboundedSender<SignedBlock>,
+    handler: ModulesHandler,
+}
+
+impl TestServer {
+    pub async fn start(data_directory: &Path, blocks: Vec<SignedBlock>) -> Result<Self> {
+        let (node_url, sent_txs) = spawn_node_stub().await;
+        let (more_blocks, more_blocks_rx) = mpsc::unbounded_channel();
+        let da_address = spawn_fake_da(blocks, more_blocks_rx).await?;
+        let bus = SharedMessageBus::new(BusMetrics::global("test".to_string()));
+        let mut handler = ModulesHandler::new(&bus).await;
+
+        let mut config = Conf::new()?;
+        config.data_directory = data_directory.to_path_buf();
+        config
+            .admin
+            .api_keys
+            .insert("tests".to_string(), ADMIN_API_KEY.to_string());
+
+        let api = Arc::new(BuildApiContextInner {
+            router: Mutex::new(Some(Router::new())),
+            openapi: Default::default(),
+        });
+
+        let indexer_api = Arc::new(BuildApiContextInner {
+            router: Mutex::new(Some(Router::new())),
+            openapi: Default::default(),
+        });
+        handler
+            .build_module::<ContractStateIndexer<FaucetCustomState, IndexerEvent>>(
+                ContractStateIndexerCtx {
+                    contract_name: CONTRACT_NAME.into(),
+                    data_directory: data_directory.to_path_buf(),
+                    api: indexer_api.clone(),
+                },
+            )
+            .await?;
+        let indexer_router = indexer_api
+            .router
+            .lock()
+            .expect("Context router should be available")
+            .take()
+            .expect("Context router should be available");
+
+        handler
+            .build_module::<AppModule>(Arc::new(AppModuleCtx {
+                api: api.clone(),
+                node_client: Arc::new(NodeApiHttpClient::new(node_url)?),
+                faucet_cn: CONTRACT_NAME.into(),
+                program_id: ProgramId(PROGRAM_ID.to_vec()),
+                config: Arc::new(config),
+                indexer: indexer_router.clone(),
+            }))
+            .await?;
+
+        handler
+            .build_module::<DAListener>(DAListenerConf {
+                data_directory: data_directory.to_path_buf(),
+                da_read_from: da_address,
+                timeout_client_secs: 10,
+                start_block: None,
+            })
+            .await?;
+
+        let router = api
+            .router
+            .lock()
+            .expect("Context router should be available")
+            .take()
+            .expect("Context router should be available")
+            .merge(indexer_router);
+
+        let port = free_port();
+        handler
+            .build_module::<RestServer>(RestServerCtx {
+                port,
+                max_body_size: 1_000_000,
+                registry: Registry::new(),
+                router,
+            })
+            .await?;
+
+        handler.start_modules().await?;
+
+        Ok(TestServer {
+            url: format!("http://127.0.0.1:{port}"),
+            sent_txs,
+            more_blocks,
+            handler,
+        })
+    }
+
+    pub async fn get(&self, path: &str) -> Result<reqwest::Response> {
+        Ok(reqwest::get(format!("{}{}", self.url, path)).await?)
+    }
+
+    pub async fn post(&self, path: &str, body: &serde_json::Value) -> Result<reqwest::Response> {
+        Ok(reqwest::Client::new()
+            .post(format!("{}{}", self.url, path))
+            .json(body)
+            .send()
+            .await?)
+    }
+
+    /// Streams `blocks` after the ones the server started with.
+    pub fn stream_blocks(&self, blocks: Vec<SignedBlock>) {
+        for block in blocks {
+            let _ = self.more_blocks.send(block);
+        }
+    }
+
+    /// The last transaction the server sent to the node.
+    pub fn last_sent_tx(&self) -> Option<BlobTransaction> {
+        self.sent_txs.lock().ok()?.last().cloned()
+    }
+
+    /// Polls `path` until `check` accepts the json body, or gives up after a few seconds.
+    pub async fn wait_for<T, F>(&self, path: &str, check: F) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+        F: Fn(&T) -> bool,
+    {
+        for _ in 0..50 {
+            if let Ok(resp) = self.get(path).await {
+                if resp.status().is_success() {
+                    let body: T = resp.json().await?;
+                    if check(&body) {
+                        return Ok(body);
+                    }
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        bail!("Timed out waiting on {path}")
+    }
+}
+
+pub fn indexer_path(route: &str) -> String {
+    format!("/v1/indexer/contract/{CONTRACT_NAME}/{route}")
+}
@@ -0,0 +1,18 @@
+use server::conf::Conf;
+
+#[test]
+fn logged_config_does_not_leak_admin_secrets() -> anyhow::Result<()> {
+    let mut config = Conf::new()?;
+    config
+        .admin
+        .api_keys
+        .insert("ops".to_string(), "key-value".to_string());
+    config.admin.hmac_secret = Some("secret-value".to_string());
+
+    let logged = format!("{config:?}");
+    assert!(logged.contains("ops"));
+    assert!(!logged.contains("key-value"));
+    assert!(!logged.contains("secret-value"));
+
+    Ok(())
+}
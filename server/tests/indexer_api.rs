@@ -0,0 +1,296 @@
+mod common;
+
+use std::collections::HashMap;
+
+use common::{indexer_path, BlockBuilder, TestServer};
+use contract1::FaucetAction;
+
+#[derive(serde::Deserialize)]
+struct LeaderboardResponse {
+    #[allow(dead_code)]
+    leaderboard: HashMap<String, u128>,
+    rank: Option<usize>,
+}
+
+#[tokio::test]
+async fn settled_clicks_show_in_balance_and_leaderboard() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let blocks = BlockBuilder::default()
+        .register_faucet()
+        .settled(vec![
+            ("harness_alice@faucet", FaucetAction::Click),
+            ("harness_alice@faucet", FaucetAction::Click),
+            ("harness_bob@faucet", FaucetAction::Click),
+        ])
+        .settled(vec![("harness_alice@faucet", FaucetAction::Click)])
+        .build();
+
+    let server = TestServer::start(dir.path(), blocks).await?;
+
+    let alice: u128 = server
+        .wait_for(&indexer_path("balance/harness_alice"), |b: &u128| *b == 3)
+        .await?;
+    assert_eq!(alice, 3);
+
+    let bob: u128 = server
+        .wait_for(&indexer_path("balance/harness_bob"), |b: &u128| *b == 1)
+        .await?;
+    assert_eq!(bob, 1);
+
+    let alice_rank = server
+        .wait_for(
+            &indexer_path("leaderboard/harness_alice"),
+            |r: &LeaderboardResponse| r.rank.is_some(),
+        )
+        .await?
+        .rank;
+    let bob_rank = server
+        .wait_for(
+            &indexer_path("leaderboard/harness_bob"),
+            |r: &LeaderboardResponse| r.rank.is_some(),
+        )
+        .await?
+        .rank;
+    assert!(alice_rank < bob_rank, "{alice_rank:?} vs {bob_rank:?}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unknown_account_ranks_last() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let blocks = BlockBuilder::default()
+        .register_faucet()
+        .settled(vec![("harness_carol@faucet", FaucetAction::Click)])
+        .build();
+
+    let server = TestServer::start(dir.path(), blocks).await?;
+    server
+        .wait_for(&indexer_path("balance/harness_carol"), |b: &u128| *b == 1)
+        .await?;
+
+    let resp: LeaderboardResponse = server
+        .get(&indexer_path("leaderboard/harness_nobody"))
+        .await?
+        .json()
+        .await?;
+    let carol: LeaderboardResponse = server
+        .get(&indexer_path("leaderboard/harness_carol"))
+        .await?
+        .json()
+        .await?;
+    assert!(resp.rank > carol.rank);
+
+    Ok(())
+}
+
+#[derive(serde::Deserialize)]
+struct PlayerResponse {
+    identity: String,
+    state: serde_json::Value,
+    rank: Option<usize>,
+    unpaid_cashout: u128,
+}
+
+#[tokio::test]
+async fn player_profile_reflects_contract_state() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let blocks = BlockBuilder::default()
+        .register_faucet()
+        .settled(vec![
+            ("harness_dave@faucet", FaucetAction::Click),
+            ("harness_dave@faucet", FaucetAction::Click),
+        ])
+        .build();
+
+    let server = TestServer::start(dir.path(), blocks).await?;
+
+    let dave: PlayerResponse = server
+        .wait_for(
+            &indexer_path("player/harness_dave"),
+            |p: &PlayerResponse| p.unpaid_cashout == 2,
+        )
+        .await?;
+    assert_eq!(dave.identity, "harness_dave@faucet");
+    assert_eq!(dave.state["multiplier"], 1);
+    assert!(dave.rank.is_some());
+
+    let nobody = server.get(&indexer_path("player/harness_nobody")).await?;
+    assert_eq!(nobody.status(), 404);
+
+    Ok(())
+}
+
+#[derive(serde::Deserialize)]
+struct UnlockedAchievement {
+    achievement: String,
+}
+
+#[tokio::test]
+async fn first_click_unlocks_an_achievement() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let blocks = BlockBuilder::default()
+        .register_faucet()
+        .settled(vec![("harness_erin@faucet", FaucetAction::Click)])
+        .build();
+
+    let server = TestServer::start(dir.path(), blocks).await?;
+
+    let achievements: Vec<UnlockedAchievement> = server
+        .wait_for(
+            &indexer_path("player/harness_erin/achievements"),
+            |a: &Vec<UnlockedAchievement>| !a.is_empty(),
+        )
+        .await?;
+    assert!(achievements.iter().any(|a| a.achievement == "FirstClick"));
+
+    Ok(())
+}
+
+#[derive(serde::Deserialize)]
+struct TeamStanding {
+    name: String,
+    points: u128,
+}
+
+#[derive(serde::Deserialize)]
+struct TeamResponse {
+    members: Vec<(String, u128)>,
+}
+
+#[tokio::test]
+async fn team_clicks_add_up_on_the_team_leaderboard() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let join = |team: &str| FaucetAction::JoinTeam {
+        team: team.to_string(),
+    };
+    let blocks = BlockBuilder::default()
+        .with_admins(&["admin@wallet"])
+        .register_faucet()
+        .settled(vec![
+            ("harness_frank@faucet", join("red")),
+            ("harness_frank@faucet", FaucetAction::Click),
+            ("harness_grace@faucet", join("blue")),
+            ("harness_grace@faucet", FaucetAction::Click),
+            ("harness_heidi@faucet", join("red")),
+        ])
+        .settled(vec![
+            ("harness_frank@faucet", FaucetAction::Click),
+            ("harness_heidi@faucet", FaucetAction::Click),
+            (
+                "admin@wallet",
+                FaucetAction::Ban {
+                    identity: "harness_heidi@faucet".into(),
+                    reason: "bot".to_string(),
+                },
+            ),
+        ])
+        .build();
+
+    let server = TestServer::start(dir.path(), blocks).await?;
+
+    let teams: Vec<TeamStanding> = server
+        .wait_for(
+            &indexer_path("teams/leaderboard"),
+            |t: &Vec<TeamStanding>| t.iter().map(|t| t.points).sum::<u128>() == 4,
+        )
+        .await?;
+    assert_eq!(teams[0].name, "red");
+    // Banned members' points stay in the on-chain total.
+    assert_eq!(teams[0].points, 3);
+
+    let red: TeamResponse = server
+        .wait_for(&indexer_path("teams/red"), |t: &TeamResponse| {
+            t.members.len() == 1
+        })
+        .await?;
+    assert_eq!(red.members[0].0, "harness_frank@faucet");
+
+    let unknown = server.get(&indexer_path("teams/green")).await?;
+    assert_eq!(unknown.status(), 404);
+
+    Ok(())
+}
+
+#[derive(serde::Deserialize)]
+struct SettingsResponse {
+    powerups: Vec<serde_json::Value>,
+    click_difficulty: u8,
+}
+
+#[tokio::test]
+async fn settings_follow_the_indexed_contract_state() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let blocks = BlockBuilder::default()
+        .with_admins(&["admin@wallet"])
+        .register_faucet()
+        .settled(vec![(
+            "admin@wallet",
+            FaucetAction::SetClickDifficulty { difficulty: 4 },
+        )])
+        .build();
+
+    let server = TestServer::start(dir.path(), blocks).await?;
+
+    let settings: SettingsResponse = server
+        .wait_for(&indexer_path("settings"), |s: &SettingsResponse| {
+            s.click_difficulty == 4
+        })
+        .await?;
+    assert_eq!(settings.powerups.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn banned_players_are_hidden_from_the_public_views() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let blocks = BlockBuilder::default()
+        .with_admins(&["admin@wallet"])
+        .register_faucet()
+        .settled(vec![
+            ("harness_alice@faucet", FaucetAction::Click),
+            ("harness_bob@faucet", FaucetAction::Click),
+            // Rejected, so that both players have a last error.
+            (
+                "harness_alice@faucet",
+                FaucetAction::BuyPowerup {
+                    name: "unknown".to_string(),
+                },
+            ),
+            (
+                "harness_bob@faucet",
+                FaucetAction::BuyPowerup {
+                    name: "unknown".to_string(),
+                },
+            ),
+            (
+                "admin@wallet",
+                FaucetAction::Ban {
+                    identity: "harness_alice@faucet".into(),
+                    reason: "bot".to_string(),
+                },
+            ),
+        ])
+        .build();
+
+    let server = TestServer::start(dir.path(), blocks).await?;
+
+    server
+        .wait_for(&indexer_path("bans"), |bans: &Vec<serde_json::Value>| {
+            !bans.is_empty()
+        })
+        .await?;
+    for route in ["balance", "player", "history", "last_error"] {
+        let alice = server
+            .get(&indexer_path(&format!("{route}/harness_alice")))
+            .await?;
+        assert_eq!(alice.status(), reqwest::StatusCode::NOT_FOUND, "{route}");
+        let bob = server
+            .get(&indexer_path(&format!("{route}/harness_bob")))
+            .await?;
+        assert!(bob.status().is_success(), "{route}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::{Duration, Instant},
+};
+
+use axum::{http::HeaderMap, routing::get, Router};
+use server::{
+    conf::{RateLimitConf, RateLimitGroup},
+    rate_limit::{RateLimiter, MAX_TRACKED_CLIENTS},
+};
+
+fn limiter(trusted_proxies: Vec<IpAddr>) -> RateLimiter {
+    RateLimiter::new(&RateLimitConf {
+        trusted_proxies,
+        groups: vec![RateLimitGroup {
+            name: "api".to_string(),
+            path_prefix: "/api".to_string(),
+            burst: 2,
+            per_second: 1.0,
+        }],
+    })
+}
+
+#[test]
+fn bucket_refills_over_time() {
+    let limiter = limiter(vec![]);
+    let client = Some("10.0.0.1".parse().unwrap());
+    let other = Some("10.0.0.2".parse().unwrap());
+    let start = Instant::now();
+
+    assert_eq!(limiter.group_of("/api/click"), Some(0));
+    assert_eq!(limiter.group_of("/_health"), None);
+
+    assert!(limiter.check(0, client, start).is_ok());
+    assert!(limiter.check(0, client, start).is_ok());
+    let retry_after = limiter.check(0, client, start).unwrap_err();
+    assert_eq!(retry_after, Duration::from_secs(1));
+
+    // Other clients have their own bucket.
+    assert!(limiter.check(0, other, start).is_ok());
+
+    assert!(limiter
+        .check(0, client, start + Duration::from_secs(1))
+        .is_ok());
+}
+
+#[test]
+fn forwarded_for_is_only_trusted_from_proxies() {
+    let proxy: IpAddr = "192.168.0.1".parse().unwrap();
+    let limiter = limiter(vec![proxy]);
+    let mut headers = HeaderMap::new();
+    headers.insert("x-forwarded-for", "1.1.1.1, 2.2.2.2".parse().unwrap());
+
+    let stranger: IpAddr = "3.3.3.3".parse().unwrap();
+    assert_eq!(limiter.client_ip(Some(stranger), &headers), Some(stranger));
+
+    // The right-most address not belonging to a proxy is the one our proxy saw.
+    assert_eq!(
+        limiter.client_ip(Some(proxy), &headers),
+        Some("2.2.2.2".parse().unwrap())
+    );
+}
+
+#[test]
+fn unknown_peers_share_one_bucket() {
+    let limiter = limiter(vec![]);
+    let mut headers = HeaderMap::new();
+    headers.insert("x-forwarded-for", "1.1.1.1".parse().unwrap());
+
+    assert_eq!(limiter.client_ip(None, &headers), None);
+}
+
+#[test]
+fn full_tables_evict_the_least_recently_used_buckets_at_once() {
+    let limiter = limiter(vec![]);
+    let start = Instant::now();
+
+    for i in 0..MAX_TRACKED_CLIENTS as u32 {
+        let client = Some(IpAddr::from(i.to_be_bytes()));
+        let now = start + Duration::from_micros(u64::from(i));
+        assert!(limiter.check(0, client, now).is_ok());
+    }
+    assert_eq!(limiter.tracked_clients(), MAX_TRACKED_CLIENTS);
+
+    let newcomer = Some("255.255.255.255".parse().unwrap());
+    let now = start + Duration::from_millis(200);
+    assert!(limiter.check(0, newcomer, now).is_ok());
+    assert_eq!(limiter.tracked_clients(), MAX_TRACKED_CLIENTS / 2 + 1);
+
+    // The most recent clients kept their bucket.
+    let last = Some(IpAddr::from((MAX_TRACKED_CLIENTS as u32 - 1).to_be_bytes()));
+    assert!(limiter.check(0, last, now).is_ok());
+    assert_eq!(limiter.tracked_clients(), MAX_TRACKED_CLIENTS / 2 + 1);
+}
+
+async fn serve_click(limiter: RateLimiter) -> anyhow::Result<String> {
+    let router = limiter.layer(Router::new().route("/api/click", get(|| async { "ok" })));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}/api/click", listener.local_addr()?);
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+    });
+    Ok(url)
+}
+
+#[tokio::test]
+async fn middleware_limits_each_forwarded_client_separately() -> anyhow::Result<()> {
+    let url = serve_click(limiter(vec!["127.0.0.1".parse()?])).await?;
+
+    let client = reqwest::Client::new();
+    let click = |ip: &'static str| client.get(&url).header("x-forwarded-for", ip).send();
+
+    assert!(click("1.1.1.1").await?.status().is_success());
+    assert!(click("1.1.1.1").await?.status().is_success());
+    let limited = click("1.1.1.1").await?;
+    assert_eq!(limited.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(limited.headers()["retry-after"], "1");
+
+    // Another client still has its whole bucket.
+    assert!(click("2.2.2.2").await?.status().is_success());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn middleware_ignores_forwarded_for_from_untrusted_peers() -> anyhow::Result<()> {
+    let url = serve_click(limiter(vec![])).await?;
+
+    let client = reqwest::Client::new();
+    let click = |ip: &'static str| client.get(&url).header("x-forwarded-for", ip).send();
+
+    assert!(click("1.1.1.1").await?.status().is_success());
+    assert!(click("2.2.2.2").await?.status().is_success());
+    // Made up addresses don't buy a new bucket.
+    let limited = click("3.3.3.3").await?;
+    assert_eq!(limited.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+
+    Ok(())
+}